@@ -0,0 +1,788 @@
+//! AI module
+//!
+//! Contains the `Bot` and `GreedyBot` computer opponents.
+//!
+//! The `Bot` uses a depth-limited minimax search with alpha-beta pruning. Every opponent is
+//! assumed to play against the bot, so the search alternates between the bot and its teammates
+//! maximizing and each opponent minimizing the score in turn order. Given a time budget instead, it searches one move
+//! deeper at a time until the budget runs out.
+//!
+//! Positions reached again by playing the same moves in another order are looked up in a
+//! transposition table instead of being searched again.
+//!
+//! The `GreedyBot` only looks one move ahead, so it answers instantly even on large boards, and
+//! the `RandomBot` plays any open column. A `Difficulty` picks one of them for a player.
+//!
+//! The position evaluation used by the `Bot` is public as `evaluate`, with its weights as
+//! constants, so custom strategies can build on it and its tuning can be experimented with.
+
+use super::board::{Board, LineCache};
+use super::rng::Rng;
+use super::strategy::{Command, Strategy};
+use super::{Game, Player, WinDirections};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Score assigned to a won position.
+const WIN_SCORE: i32 = 1_000_000;
+
+/// How much an open window counts for its player, multiplied by the square of the tokens in it.
+pub const WINDOW_WEIGHT: i32 = 1;
+
+/// How much an open window counts against its player's opponents, multiplied by the square of
+/// the tokens in it.
+pub const OPPONENT_WINDOW_WEIGHT: i32 = 1;
+
+/// How much each token in the center column counts for its player.
+///
+/// On boards with an even number of columns both middle columns count.
+pub const CENTER_WEIGHT: i32 = 2;
+
+/// Represents how strong a computer opponent is.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+#[cfg_attr(
+    any(feature = "serde", feature = "config"),
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Difficulty {
+    /// A `RandomBot` that plays any open column.
+    Easy,
+    /// A `GreedyBot` that wins or blocks when it can and otherwise plays near the center.
+    Medium,
+    /// A minimax `Bot` searching as deep as the board size allows.
+    #[default]
+    Hard,
+}
+
+impl Difficulty {
+    /// Returns how many moves ahead a hard bot searches on a board with `columns` columns.
+    ///
+    /// Every extra column multiplies the work of each move searched, so wide boards are searched
+    /// less deeply to keep bots responsive.
+    pub fn search_depth(columns: usize) -> usize {
+        // Roughly 200 000 positions per move
+        let depth = (200_000f64).ln() / (columns.max(2) as f64).ln();
+        (depth as usize).clamp(2, 8)
+    }
+
+    /// Creates the strategy for a bot of this difficulty playing on `board`.
+    ///
+    /// `seed` drives the moves of easy bots, so the same seed replays the same game. `depth`
+    /// overrides the search depth of hard bots. With a `time_budget` hard bots search as deep as
+    /// they can in that time instead.
+    pub fn strategy(
+        self,
+        board: &Board,
+        seed: u64,
+        depth: Option<usize>,
+        time_budget: Option<Duration>,
+    ) -> Box<dyn Strategy> {
+        match (self, time_budget) {
+            (Difficulty::Easy, _) => Box::new(RandomBot::new(seed)),
+            (Difficulty::Medium, _) => Box::new(GreedyBot),
+            (Difficulty::Hard, Some(time_budget)) => Box::new(Bot::with_time_budget(time_budget)),
+            (Difficulty::Hard, None) => Box::new(Bot::new(
+                depth.unwrap_or_else(|| Self::search_depth(board.lane_count())),
+            )),
+        }
+    }
+}
+
+/// Represents how a score kept in the transposition table relates to the true score.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The true score is at least this high, since the search was cut off.
+    Lower,
+    /// The true score is at most this high, since no move reached alpha.
+    Upper,
+}
+
+/// Represents a position scored during a search.
+#[derive(Clone, Copy, Debug)]
+struct TableEntry {
+    /// How many moves ahead the position was searched.
+    depth: usize,
+    /// The score of the position.
+    score: i32,
+    /// How the score relates to the true score.
+    bound: Bound,
+}
+
+/// Remembers the positions scored during a search, keyed by their Zobrist hash.
+///
+/// The hash of a position is the XOR of a random key for every token on the board and one for
+/// the player to move, so placing or taking back a token only takes one more XOR.
+struct TranspositionTable {
+    /// The key of each token, by cell and owner.
+    cell_keys: Vec<u64>,
+    /// The key of each player to move.
+    turn_keys: Vec<u64>,
+    /// The number of owners a cell can have: every player, and anyone else.
+    owners: usize,
+    /// The number of columns of the board.
+    cols: usize,
+    /// The positions scored so far.
+    entries: HashMap<u64, TableEntry>,
+}
+
+impl TranspositionTable {
+    /// Creates an empty table for searches on `board` between `players` players.
+    fn new(board: &Board, players: usize) -> Self {
+        // Fixed keys keep searches reproducible
+        let mut rng = Rng::new(0x5EED_C0DE);
+        let owners = players + 1;
+        let cells = board.rows.len() * board.rows[0].len();
+
+        Self {
+            cell_keys: (0..cells * owners).map(|_| rng.next_u64()).collect(),
+            turn_keys: (0..players).map(|_| rng.next_u64()).collect(),
+            owners,
+            cols: board.rows[0].len(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the key of a token of `order[owner]` at (row, col).
+    fn cell_key(&self, row: usize, col: usize, owner: usize) -> u64 {
+        self.cell_keys[(row * self.cols + col) * self.owners + owner.min(self.owners - 1)]
+    }
+
+    /// Hashes the tokens on `board`, without the player to move.
+    fn hash(&self, board: &Board, order: &[&Player]) -> u64 {
+        board
+            .iter_cells()
+            .filter_map(|(row, col, cell)| {
+                let owner = cell.as_ref()?;
+                let index = order.iter().position(|player| player.token == owner.token);
+                Some(self.cell_key(row, col, index.unwrap_or(order.len())))
+            })
+            .fold(0, |hash, key| hash ^ key)
+    }
+}
+
+/// A computer opponent that searches the game tree with minimax.
+pub struct Bot {
+    /// How many moves ahead the bot looks. Higher is stronger but slower.
+    depth: usize,
+    /// How long the bot may think about each move, if it searches until time runs out.
+    time_budget: Option<Duration>,
+    /// When the current search has to stop, if it is timed.
+    deadline: Cell<Option<Instant>>,
+    /// Whether positions are kept in a transposition table during a search.
+    use_table: bool,
+    /// The transposition table of the current search.
+    table: RefCell<Option<TranspositionTable>>,
+    /// The number of positions visited by the last search.
+    nodes: Cell<u64>,
+    /// The lines of the board, which every scored position is scanned along.
+    lines: LineCache,
+}
+
+impl Bot {
+    /// Creates a new bot that searches `depth` moves ahead.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            time_budget: None,
+            deadline: Cell::new(None),
+            use_table: true,
+            table: RefCell::new(None),
+            nodes: Cell::new(0),
+            lines: LineCache::default(),
+        }
+    }
+
+    /// Creates a new bot that thinks for at most `time_budget` about each move.
+    ///
+    /// It searches one move ahead, then two, and so on, and plays the best move of the deepest
+    /// search it finished in time. If not even the first search finishes, it plays a legal move
+    /// close to the center.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::ai::Bot;
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::game::strategy::Strategy;
+    /// # use connect_four::Player;
+    /// # use std::time::{Duration, Instant};
+    /// let bot = Bot::with_time_budget(Duration::from_millis(50));
+    /// let board = Board::new(12, 14);
+    /// let (me, opponent) = (Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b'));
+    ///
+    /// let started = Instant::now();
+    /// let col = bot.choose_column(&board, &me, &[opponent], 4);
+    ///
+    /// assert!(board.valid_move(col));
+    /// assert!(started.elapsed() < Duration::from_secs(1));
+    /// ```
+    pub fn with_time_budget(time_budget: Duration) -> Self {
+        Self {
+            depth: 1,
+            time_budget: Some(time_budget),
+            deadline: Cell::new(None),
+            use_table: true,
+            table: RefCell::new(None),
+            nodes: Cell::new(0),
+            lines: LineCache::default(),
+        }
+    }
+
+    /// Sets whether positions are kept in a transposition table, which is on by default.
+    ///
+    /// The table makes the bot search fewer positions for the same move.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::ai::Bot;
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::game::strategy::Strategy;
+    /// # use connect_four::Player;
+    /// let (me, opponent) = (Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b'));
+    /// let board = Board::new(4, 4);
+    ///
+    /// let with_table = Bot::new(5);
+    /// let without_table = Bot::new(5).with_transposition_table(false);
+    /// with_table.choose_column(&board, &me, &[opponent.clone()], 3);
+    /// without_table.choose_column(&board, &me, &[opponent], 3);
+    ///
+    /// assert!(with_table.nodes_searched() < without_table.nodes_searched());
+    /// ```
+    pub fn with_transposition_table(mut self, enabled: bool) -> Self {
+        self.use_table = enabled;
+        self
+    }
+
+    /// Returns the number of positions visited while choosing the last move.
+    pub fn nodes_searched(&self) -> u64 {
+        self.nodes.get()
+    }
+
+    /// Returns the key of a token of `order[owner]` at (row, col), or 0 without a table.
+    fn cell_key(&self, row: usize, col: usize, owner: usize) -> u64 {
+        self.table
+            .borrow()
+            .as_ref()
+            .map_or(0, |table| table.cell_key(row, col, owner))
+    }
+
+    /// Checks if the current search ran out of time.
+    fn out_of_time(&self) -> bool {
+        self.deadline
+            .get()
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns the best column for `order[0]`, searching `depth` moves ahead.
+    ///
+    /// `first` is searched first, e.g. the best column of a shallower search, which prunes more
+    /// branches. Returns None if there is no open column or time ran out before the search
+    /// finished.
+    fn best_column(
+        &self,
+        board: &mut Board,
+        order: &[&Player],
+        depth: usize,
+        tokens_to_win: usize,
+        first: Option<usize>,
+    ) -> Option<usize> {
+        let me = order[0];
+        let mut columns = candidate_columns(board);
+        if let Some(index) = columns.iter().position(|&col| Some(col) == first) {
+            columns[..=index].rotate_right(1);
+        }
+
+        let hash = self
+            .table
+            .borrow()
+            .as_ref()
+            .map_or(0, |table| table.hash(board, order));
+        let mut best_col = None;
+        let mut best_score = i32::MIN;
+
+        // A banned center only applies to the move being chosen, not to the moves after it
+        let banned = board.ban_first_center;
+        for col in columns {
+            board.ban_first_center = banned;
+            let (row, cell_col) = match board.place_token(col, me.clone()) {
+                Ok(cell) => cell,
+                Err(_) => continue,
+            };
+            board.ban_first_center = false;
+            let score = self.minimax(
+                board,
+                order,
+                1,
+                depth.saturating_sub(1),
+                tokens_to_win,
+                (row, cell_col),
+                hash ^ self.cell_key(row, cell_col, 0),
+                best_score,
+                i32::MAX,
+            );
+            board.rows[row][cell_col] = None;
+
+            if best_col.is_none() || score > best_score {
+                best_col = Some(col);
+                best_score = score;
+            }
+        }
+
+        board.ban_first_center = banned;
+
+        // An interrupted search may have missed the best column
+        if self.out_of_time() {
+            return None;
+        }
+        best_col
+    }
+
+    /// Scores the board from `order[0]`'s perspective, searching `depth` more moves.
+    ///
+    /// `ply` is the number of moves made since the root, used to find whose turn it is.
+    /// `last` is the (row, column) of the token placed to reach this board, and `hash` the hash
+    /// of its tokens in the transposition table.
+    #[allow(clippy::too_many_arguments)]
+    fn minimax(
+        &self,
+        board: &mut Board,
+        order: &[&Player],
+        ply: usize,
+        depth: usize,
+        tokens_to_win: usize,
+        last: (usize, usize),
+        hash: u64,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        let me = order[0];
+        self.nodes.set(self.nodes.get() + 1);
+
+        // The result is thrown away once time runs out, so stop right there
+        if self.out_of_time() {
+            return 0;
+        }
+
+        // Prefer quicker wins and slower losses. Bots assume the standard rules, where
+        // overlines and lines in every direction win.
+        if let Some(winner) = Game::find_winner_at(
+            board,
+            last.0,
+            last.1,
+            tokens_to_win,
+            false,
+            WinDirections::ALL,
+        ) {
+            return if winner.player.is_teammate(me) {
+                WIN_SCORE + depth as i32
+            } else {
+                -WIN_SCORE - depth as i32
+            };
+        }
+
+        if depth == 0 || board.is_board_full() {
+            let lines = self.lines.lines(board, tokens_to_win);
+            return evaluate_lines(board, &lines, me, tokens_to_win);
+        }
+
+        let turn = ply % order.len();
+        let key = self
+            .table
+            .borrow()
+            .as_ref()
+            .map_or(0, |table| hash ^ table.turn_keys[turn]);
+
+        // A position searched at least as deep before needs no search
+        let entry = self
+            .table
+            .borrow()
+            .as_ref()
+            .and_then(|table| table.entries.get(&key).copied());
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            match entry.bound {
+                Bound::Exact => return entry.score,
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return entry.score;
+            }
+        }
+        let (window_alpha, window_beta) = (alpha, beta);
+
+        let player = order[turn];
+        let maximizing = player.is_teammate(me);
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+
+        for col in candidate_columns(board) {
+            let (row, cell_col) = match board.place_token(col, player.clone()) {
+                Ok(cell) => cell,
+                Err(_) => continue,
+            };
+            let score = self.minimax(
+                board,
+                order,
+                ply + 1,
+                depth - 1,
+                tokens_to_win,
+                (row, cell_col),
+                hash ^ self.cell_key(row, cell_col, turn),
+                alpha,
+                beta,
+            );
+            board.rows[row][cell_col] = None;
+
+            if maximizing {
+                best = best.max(score);
+                alpha = alpha.max(best);
+            } else {
+                best = best.min(score);
+                beta = beta.min(best);
+            }
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // Scores of an interrupted search are made up, so they must not be kept
+        if !self.out_of_time() {
+            if let Some(table) = self.table.borrow_mut().as_mut() {
+                let bound = if best <= window_alpha {
+                    Bound::Upper
+                } else if best >= window_beta {
+                    Bound::Lower
+                } else {
+                    Bound::Exact
+                };
+                table.entries.insert(
+                    key,
+                    TableEntry {
+                        depth,
+                        score: best,
+                        bound,
+                    },
+                );
+            }
+        }
+
+        best
+    }
+}
+
+impl Strategy for Bot {
+    fn choose_column(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize {
+        let mut board = board.clone();
+        let order: Vec<&Player> = std::iter::once(me).chain(opponents).collect();
+
+        // Every move starts with a fresh table, shared by the searches of every depth
+        self.nodes.set(0);
+        *self.table.borrow_mut() = self
+            .use_table
+            .then(|| TranspositionTable::new(&board, order.len()));
+
+        let Some(time_budget) = self.time_budget else {
+            let col = self.best_column(&mut board, &order, self.depth, tokens_to_win, None);
+            *self.table.borrow_mut() = None;
+            return col.unwrap_or(0);
+        };
+
+        // Deepen the search until time runs out, or until it covers every empty cell
+        self.deadline.set(Some(Instant::now() + time_budget));
+        let mut best_col = candidate_columns(&board).first().copied();
+        let empty_cells = board
+            .iter_cells()
+            .filter(|(_, _, cell)| cell.is_none())
+            .count();
+        for depth in 1..=empty_cells {
+            match self.best_column(&mut board, &order, depth, tokens_to_win, best_col) {
+                Some(col) => best_col = Some(col),
+                None => break,
+            }
+        }
+        self.deadline.set(None);
+        *self.table.borrow_mut() = None;
+
+        best_col.unwrap_or(0)
+    }
+
+    /// Drops a token where `choose_column` decides.
+    ///
+    /// If the board is full but the game goes on, the Pop Out variant is being played and the bot
+    /// pops out one of its own tokens instead.
+    fn choose_command(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        if board.is_board_full() {
+            if let Some(&lane) = board.pop_out_lanes(me).first() {
+                return Command::PopOut(lane);
+            }
+        }
+
+        Command::Drop(self.choose_column(board, me, opponents, tokens_to_win))
+    }
+}
+
+/// A computer opponent that looks only one move ahead.
+///
+/// It plays a winning move if there is one, otherwise blocks the opponents' winning moves in the
+/// order they move, otherwise plays the open column closest to the center.
+pub struct GreedyBot;
+
+impl Strategy for GreedyBot {
+    fn choose_column(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize {
+        suggest_column(board, me, opponents, tokens_to_win).unwrap_or(0)
+    }
+}
+
+/// A computer opponent that drops its tokens into random open columns.
+///
+/// Every open column is equally likely. The moves only depend on the seed and the boards it is
+/// shown, so a seed and a game configuration always produce the same game.
+pub struct RandomBot {
+    rng: RefCell<Rng>,
+}
+
+impl RandomBot {
+    /// Creates a new random bot from the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: RefCell::new(Rng::new(seed)),
+        }
+    }
+}
+
+impl Strategy for RandomBot {
+    fn choose_column(
+        &self,
+        board: &Board,
+        _me: &Player,
+        _opponents: &[Player],
+        _tokens_to_win: usize,
+    ) -> usize {
+        let open = board.open_columns();
+        if open.is_empty() {
+            return 0;
+        }
+
+        open[self.rng.borrow_mut().next_below(open.len())]
+    }
+}
+
+/// Suggests a column for `me`, looking only one move ahead.
+///
+/// Picks a column that wins right away, otherwise one that blocks the next opponent able to win
+/// on their turn, otherwise the playable column closest to the center.
+pub(crate) fn suggest_column(
+    board: &Board,
+    me: &Player,
+    opponents: &[Player],
+    tokens_to_win: usize,
+) -> Option<usize> {
+    let columns = candidate_columns(board);
+    let mut board = board.clone();
+
+    // Win now if possible, then block opponents in the order they move
+    for player in std::iter::once(me).chain(opponents) {
+        if let Some(&col) = columns
+            .iter()
+            .find(|&&col| wins_with(&mut board, col, player, tokens_to_win))
+        {
+            return Some(col);
+        }
+    }
+
+    columns.first().copied()
+}
+
+/// Checks whether dropping `player`'s token into `col` completes a line.
+///
+/// The board is left unchanged.
+fn wins_with(board: &mut Board, col: usize, player: &Player, tokens_to_win: usize) -> bool {
+    let Ok((row, cell_col)) = board.place_token(col, player.clone()) else {
+        return false;
+    };
+    let wins = Game::find_winner_at(
+        board,
+        row,
+        cell_col,
+        tokens_to_win,
+        false,
+        WinDirections::ALL,
+    )
+    .is_some();
+    board.rows[row][cell_col] = None;
+    wins
+}
+
+/// Returns the playable columns, ordered from the center outwards.
+///
+/// Central columns take part in more lines, so searching them first prunes more branches.
+fn candidate_columns(board: &Board) -> Vec<usize> {
+    let cols = board.lane_count();
+    let mut columns = board.open_columns();
+    columns.sort_by_key(|&col| (2 * col).abs_diff(cols - 1));
+    columns
+}
+
+/// Heuristically scores a board for `player`, higher being better.
+///
+/// Every open window of `tokens_to_win` cells, one holding no blocked cell and only tokens of a
+/// single side, counts towards that side, weighted by the square of the tokens it holds. Windows
+/// of `player` and their teammates add `WINDOW_WEIGHT` times that, windows of opponents subtract
+/// `OPPONENT_WINDOW_WEIGHT` times that. Tokens in the center column add or subtract
+/// `CENTER_WEIGHT` each, since they take part in the most lines.
+///
+/// Finished games are not recognized, so check for a winner first.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::ai::evaluate;
+/// # use connect_four::game::board::Board;
+/// # use connect_four::Player;
+/// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+/// let mut board = Board::new(6, 7);
+/// assert_eq!(evaluate(&board, &players[0], 4), 0);
+///
+/// board.place_token(3, players[0].clone()).unwrap();
+/// assert!(evaluate(&board, &players[0], 4) > 0);
+/// assert!(evaluate(&board, &players[1], 4) < 0);
+/// ```
+pub fn evaluate(board: &Board, player: &Player, tokens_to_win: usize) -> i32 {
+    evaluate_lines(
+        board,
+        &board.all_lines(tokens_to_win),
+        player,
+        tokens_to_win,
+    )
+}
+
+/// Scores a board like `evaluate`, scanning `lines`, which are the lines returned by
+/// `Board::all_lines`.
+fn evaluate_lines(
+    board: &Board,
+    lines: &[Vec<(usize, usize)>],
+    player: &Player,
+    tokens_to_win: usize,
+) -> i32 {
+    let mut score = 0;
+
+    for line in lines {
+        // Windows of a ring may run past its end and continue at its start
+        let mut line = line.clone();
+        if board.is_ring(&line) {
+            line.extend_from_within(..tokens_to_win - 1);
+        }
+
+        for window in line.windows(tokens_to_win) {
+            if window.iter().any(|&(row, col)| board.is_blocked(row, col)) {
+                continue;
+            }
+
+            let mut owner: Option<&Player> = None;
+            let mut count = 0;
+            let mut contested = false;
+
+            for token in window
+                .iter()
+                .filter_map(|&(row, col)| board.get(row, col).and_then(Option::as_ref))
+            {
+                match owner {
+                    Some(owner) if !owner.is_teammate(token) => {
+                        contested = true;
+                        break;
+                    }
+                    _ => {
+                        owner = Some(token);
+                        count += 1;
+                    }
+                }
+            }
+
+            if contested {
+                continue;
+            }
+
+            if let Some(owner) = owner {
+                if owner.is_teammate(player) {
+                    score += WINDOW_WEIGHT * count * count;
+                } else {
+                    score -= OPPONENT_WINDOW_WEIGHT * count * count;
+                }
+            }
+        }
+    }
+
+    // Central lanes take part in the most lines
+    let lanes = board.lane_count();
+    for (row, col, cell) in board.iter_cells() {
+        let Some(token) = cell else {
+            continue;
+        };
+        if (2 * board.lane_of(row, col)).abs_diff(lanes - 1) > 1 {
+            continue;
+        }
+
+        if token.is_teammate(player) {
+            score += CENTER_WEIGHT;
+        } else {
+            score -= CENTER_WEIGHT;
+        }
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bots_set_up_the_winning_move_of_a_teammate() {
+        let alice = Player {
+            team: Some(0),
+            ..Player::with_token("Alice", 'a')
+        };
+        let bob = Player {
+            team: Some(1),
+            ..Player::with_token("Bob", 'b')
+        };
+        let carol = Player {
+            team: Some(0),
+            ..Player::with_token("Carol", 'c')
+        };
+        let players = [alice.clone(), bob.clone(), carol.clone()];
+        // Carol can complete the second row once column 3 has a token to land on
+        let board = Board::from_str(
+            "       \n       \n       \n       \nccc    \nbab    ",
+            &players,
+        )
+        .unwrap();
+
+        let col = Bot::new(3).choose_column(&board, &alice, &[carol, bob], 4);
+
+        assert_eq!(col, 3);
+    }
+}
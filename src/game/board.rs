@@ -1,66 +1,917 @@
+use super::render::DisplaySettings;
+use super::util::Labels;
 use super::Player;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 /// Represents a row of the game board.
 pub type BoardRow = Vec<BoardCell>;
 /// Represents a cell on the game board.
 pub type BoardCell = Option<Player>;
 
+/// The symbol of a blocked cell when a board is read from or written to text.
+pub const BLOCKED_CELL: &str = "#";
+
+/// Represents an invalid operation on the game board.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BoardError {
+    /// The column does not exist on the board.
+    ColumnOutOfRange(usize),
+    /// The column has no empty cells left.
+    ColumnFull(usize),
+    /// The bottom token of the column does not belong to the player.
+    NotYourToken(usize),
+    /// A textual board could not be parsed.
+    InvalidBoard(String),
+    /// The cell at (row, column) does not exist on the board.
+    CellOutOfRange(usize, usize),
+    /// The cell at (row, column) already holds a token.
+    CellOccupied(usize, usize),
+    /// The cell at (row, column) is blocked and can never hold a token.
+    CellBlocked(usize, usize),
+    /// The center column may not be played on the first move.
+    CenterBanned(usize),
+}
+
+impl BoardError {
+    /// Describes the error to the players, with lanes and cells labelled by `labels`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::BoardError;
+    /// # use connect_four::game::util::{ColumnLabels, Labels};
+    /// let err = BoardError::CellOccupied(0, 2);
+    ///
+    /// assert_eq!(err.to_string(), "Cell 0,2 is already taken.");
+    /// let labels = Labels::new(true, ColumnLabels::Alpha, 7);
+    /// assert_eq!(err.describe(&labels), "Cell 1,C is already taken.");
+    /// ```
+    pub fn describe(&self, labels: &Labels) -> String {
+        match self {
+            BoardError::ColumnOutOfRange(col) => {
+                format!("Column {} is out of range.", labels.lane(*col))
+            }
+            BoardError::ColumnFull(col) => format!("Column {} is full.", labels.lane(*col)),
+            BoardError::NotYourToken(col) => format!(
+                "The bottom token of column {} is not yours.",
+                labels.lane(*col)
+            ),
+            BoardError::InvalidBoard(reason) => format!("Invalid board: {}", reason),
+            BoardError::CellOutOfRange(row, col) => format!(
+                "Cell {},{} is out of range.",
+                labels.row(*row),
+                labels.lane(*col)
+            ),
+            BoardError::CellOccupied(row, col) => format!(
+                "Cell {},{} is already taken.",
+                labels.row(*row),
+                labels.lane(*col)
+            ),
+            BoardError::CellBlocked(row, col) => format!(
+                "Cell {},{} is blocked.",
+                labels.row(*row),
+                labels.lane(*col)
+            ),
+            BoardError::CenterBanned(col) => format!(
+                "Column {} is in the center and may not be played on the first move.",
+                labels.lane(*col)
+            ),
+        }
+    }
+}
+
+/// Lanes and cells are numbered from 0, see `describe` for the labels players chose.
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(&Labels::default()))
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+/// Represents the direction tokens fall in.
+///
+/// With `Down` and `Up` gravity players choose a column, with `Left` and `Right` gravity they
+/// choose a row. Either way the chosen line is called a lane.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gravity {
+    /// Tokens fall to the bottom row.
+    #[default]
+    Down,
+    /// Tokens rise to the top row.
+    Up,
+    /// Tokens slide to the leftmost column.
+    Left,
+    /// Tokens slide to the rightmost column.
+    Right,
+}
+
+/// Keeps the lines of a board layout between calls, see `Board::all_lines`.
+///
+/// The lines only depend on the size of the board, whether it wraps around and the tokens needed
+/// to win, so a game or a bot computes them once and shares them for as long as the layout stays
+/// the same.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::board::{Board, LineCache};
+/// # use std::sync::Arc;
+/// let cache = LineCache::default();
+/// let board = Board::new(3, 3);
+///
+/// let lines = cache.lines(&board, 3);
+/// assert_eq!(*lines, board.all_lines(3));
+/// assert!(Arc::ptr_eq(&lines, &cache.lines(&board, 3)));
+/// assert_eq!(cache.lines(&board, 2).len(), 12);
+/// ```
+#[derive(Debug, Default)]
+pub struct LineCache {
+    /// The (rows, columns, wrap, tokens to win) the lines were computed for, and the lines.
+    entry: Mutex<Option<(LineLayout, SharedLines)>>,
+}
+
+/// The (rows, columns, wrap, tokens to win) the lines of a board depend on.
+type LineLayout = (usize, usize, bool, usize);
+
+/// Lines of a board shared between callers.
+type SharedLines = Arc<Vec<Vec<(usize, usize)>>>;
+
+impl LineCache {
+    /// Returns the lines of `board` long enough to hold `tokens_to_win` tokens, computing them
+    /// only if the layout changed since the last call.
+    pub fn lines(&self, board: &Board, tokens_to_win: usize) -> Arc<Vec<Vec<(usize, usize)>>> {
+        let layout = (
+            board.rows.len(),
+            board.rows[0].len(),
+            board.wrap,
+            tokens_to_win,
+        );
+        let mut entry = self.entry.lock().unwrap();
+
+        match entry.as_ref() {
+            Some((cached, lines)) if *cached == layout => Arc::clone(lines),
+            _ => {
+                let lines = Arc::new(board.all_lines(tokens_to_win));
+                *entry = Some((layout, Arc::clone(&lines)));
+                lines
+            }
+        }
+    }
+}
+
 /// Represents a Connect Four game board.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
     /// Stores state of the game board.
     pub rows: Vec<BoardRow>,
+    /// The direction tokens fall in.
+    pub gravity: Gravity,
+    /// The (row, column) of every cell that can never hold a token.
+    ///
+    /// Blocked cells break lines, and dropped tokens come to rest on top of them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blocked: BTreeSet<(usize, usize)>,
+    /// Whether the left and right edges are joined, so rows and diagonals wrap around.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wrap: bool,
+    /// The most tokens a column can be stacked up to, if lower than the number of rows.
+    ///
+    /// Cells above this height can't be reached by dropping tokens and simply stay empty.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_height: Option<usize>,
+    /// Whether the center column, or both center columns of an even board, may not be played,
+    /// to take away some of the first player's advantage.
+    ///
+    /// A `Game` sets this for the first move only, see `Game::set_ban_first_center`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ban_first_center: bool,
+    /// The (row, column) of the token placed last, which `display` marks.
+    ///
+    /// Set whenever a token is placed, and cleared once tokens are popped out or the board is
+    /// reset.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub last_placed: Option<(usize, usize)>,
+    /// The (row, column) of a token that is only previewed, see `preview`, which `display`
+    /// draws faintly.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub ghost: Option<(usize, usize)>,
 }
 
 impl Board {
     /// Creates a new game board with the specified number of rows and columns.
     pub fn new(row_count: usize, col_count: usize) -> Self {
+        Self::with_gravity(row_count, col_count, Gravity::Down)
+    }
+
+    /// Creates a new game board where tokens fall in the given direction.
+    pub fn with_gravity(row_count: usize, col_count: usize, gravity: Gravity) -> Self {
         Self {
             rows: vec![vec![None; col_count]; row_count],
+            gravity,
+            blocked: BTreeSet::new(),
+            wrap: false,
+            max_height: None,
+            ban_first_center: false,
+            last_placed: None,
+            ghost: None,
         }
     }
 
-    /// Displays the game board.
-    pub fn display(&self) -> String {
-        let mut output = String::new();
+    /// Parses a board from text, one line per row from top to bottom.
+    ///
+    /// Each character is a player token, a space for an empty cell or `#` for a blocked cell.
+    /// Rows in the format of `display` (e.g. `[a][ ][b]`) are accepted too, without the column
+    /// headers. Tokens made of several characters, such as most emoji, can only be read from
+    /// bracketed rows.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let board = Board::from_str("   \n a#\nab ", &players).unwrap();
+    ///
+    /// assert_eq!(board.rows[2][1], Some(players[1].clone()));
+    /// assert!(board.is_blocked(1, 2));
+    ///
+    /// // Unknown tokens are reported with their cell
+    /// assert_eq!(
+    ///     Board::from_str("   \nax ", &players).err().unwrap().to_string(),
+    ///     r#"Invalid board: unknown token "x" at column 1, row 1"#
+    /// );
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// - If the text has no rows or the rows differ in length.
+    /// - If a character is not the token of any player.
+    /// - If a token floats above an empty cell.
+    pub fn from_str(s: &str, players: &[Player]) -> Result<Self, BoardError> {
+        let rows = s
+            .lines()
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .map(|(row, line)| {
+                Self::parse_row(line)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(col, symbol)| match symbol.as_str() {
+                        "" => Ok(Some(None)),
+                        token => match players.iter().find(|player| player.token == token) {
+                            Some(player) => Ok(Some(Some(player.clone()))),
+                            None if token == BLOCKED_CELL => Ok(None),
+                            None => Err(BoardError::InvalidBoard(format!(
+                                "unknown token {:?} at column {}, row {}",
+                                token, col, row
+                            ))),
+                        },
+                    })
+                    .collect::<Result<Vec<Option<BoardCell>>, BoardError>>()
+            })
+            .collect::<Result<Vec<Vec<Option<BoardCell>>>, BoardError>>()?;
 
-        // Determine the width needed to display the largest column index
-        let max_col_width = self.rows[0].len().to_string().len() + 1;
+        Self::from_rows(rows)
+    }
 
-        // Print the column headers (indices)
-        for col in 0..self.rows[0].len() {
-            // Format each column index to be right-aligned within the max width
-            output.push_str(&format!("{:width$} ", col, width = max_col_width));
+    /// Renders the board on a single line, e.g. `.../.a./ab.`.
+    ///
+    /// Rows are listed from top to bottom and separated by `/`. Each cell is a player token, `.`
+    /// if it is empty or `#` if it is blocked. The gravity is not included.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let mut board = Board::new(3, 3);
+    /// board.place_token(0, players[0].clone()).unwrap();
+    /// board.place_token(1, players[1].clone()).unwrap();
+    /// board.place_token(1, players[0].clone()).unwrap();
+    ///
+    /// assert_eq!(board.to_compact(), ".../.a./ab.");
+    /// assert_eq!(Board::from_compact(".../.a./ab.", &players).unwrap().to_compact(), ".../.a./ab.");
+    /// ```
+    pub fn to_compact(&self) -> String {
+        let mut compact = String::new();
+
+        for (row, col, cell) in self.iter_cells() {
+            if row > 0 && col == 0 {
+                compact.push('/');
+            }
+
+            compact.push_str(match cell {
+                Some(player) => player.token.as_str(),
+                None if self.is_blocked(row, col) => BLOCKED_CELL,
+                None => ".",
+            });
         }
 
-        output.push('\n');
+        compact
+    }
 
-        // Print the board rows
-        for row in &self.rows {
-            for col in row {
-                let symbol = match col {
-                    Some(player) => player.token,
-                    None => ' ',
-                };
-                output.push_str(&format!("[{:width$}]", symbol, width = max_col_width - 1));
+    /// Writes the board to the file at `path`, e.g. to keep a position for later analysis.
+    ///
+    /// Files ending in `.json` get the board as JSON when the `serde` feature is enabled, any
+    /// other file gets its compact form, see `to_compact`.
+    ///
+    /// ## Errors
+    ///
+    /// - If the file can't be written.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let board = Board::from_compact(".../.a./ab.", &players).unwrap();
+    /// let path = std::env::temp_dir().join("connect_four_save_board.txt");
+    /// board.save(&path).unwrap();
+    ///
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), ".../.a./ab.\n");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        #[cfg(feature = "serde")]
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            let json = serde_json::to_string_pretty(self).expect("boards are always serializable");
+            return std::fs::write(path, json + "\n");
+        }
+
+        std::fs::write(path, self.to_compact() + "\n")
+    }
+
+    /// Parses a board in the format of `to_compact`.
+    ///
+    /// Tokens of several characters are matched against the tokens of `players`, longest first.
+    /// Boards with tokens containing `.` or `/` cannot be read back.
+    ///
+    /// ## Errors
+    ///
+    /// - If the text has no rows or the rows differ in length.
+    /// - If a cell is not the token of any player.
+    /// - If a token floats above an empty cell.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::{Board, BoardError};
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let invalid = |s| match Board::from_compact(s, &players) {
+    ///     Err(BoardError::InvalidBoard(reason)) => reason,
+    ///     _ => panic!("{} should be invalid", s),
+    /// };
+    ///
+    /// assert_eq!(invalid("..../ab."), "row 1 has 3 cells, but the first row has 4");
+    /// assert_eq!(invalid(".../ax."), r#"unknown token at "x.", column 1, row 1"#);
+    /// assert_eq!(invalid(".a./.../ab."), "the token at column 1, row 0 floats above an empty cell");
+    ///
+    /// // Tokens may rest on blocked cells
+    /// assert!(Board::from_compact(".a./.#./ab.", &players).is_ok());
+    /// ```
+    pub fn from_compact(s: &str, players: &[Player]) -> Result<Self, BoardError> {
+        let mut by_length: Vec<&Player> = players.iter().collect();
+        by_length.sort_by_key(|player| std::cmp::Reverse(player.token.len()));
+
+        let rows = s
+            .trim()
+            .split('/')
+            .enumerate()
+            .map(|(row_index, mut line)| {
+                let mut row: Vec<Option<BoardCell>> = Vec::new();
+
+                while !line.is_empty() {
+                    if let Some(rest) = line.strip_prefix('.') {
+                        row.push(Some(None));
+                        line = rest;
+                        continue;
+                    }
+
+                    let player = by_length
+                        .iter()
+                        .find(|player| !player.token.is_empty() && line.starts_with(&player.token));
+                    if let Some(player) = player {
+                        row.push(Some(Some((*player).clone())));
+                        line = &line[player.token.len()..];
+                    } else if let Some(rest) = line.strip_prefix(BLOCKED_CELL) {
+                        row.push(None);
+                        line = rest;
+                    } else {
+                        return Err(BoardError::InvalidBoard(format!(
+                            "unknown token at {:?}, column {}, row {}",
+                            line,
+                            row.len(),
+                            row_index
+                        )));
+                    }
+                }
+
+                Ok(row)
+            })
+            .collect::<Result<Vec<Vec<Option<BoardCell>>>, BoardError>>()?;
+
+        Self::from_rows(rows)
+    }
+
+    /// Creates a board with downward gravity from parsed rows, where blocked cells are None.
+    ///
+    /// ## Errors
+    ///
+    /// - If there are no rows or the rows differ in length.
+    /// - If a token floats above an empty cell, which no drop can leave behind.
+    fn from_rows(cells: Vec<Vec<Option<BoardCell>>>) -> Result<Self, BoardError> {
+        let mut blocked = BTreeSet::new();
+        let rows: Vec<BoardRow> = cells
+            .into_iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                cells
+                    .into_iter()
+                    .enumerate()
+                    .map(|(col, cell)| {
+                        cell.unwrap_or_else(|| {
+                            blocked.insert((row, col));
+                            None
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let cols = rows.first().map_or(0, |row| row.len());
+        if cols == 0 {
+            return Err(BoardError::InvalidBoard("the board is empty".to_string()));
+        }
+        if let Some((row, cells)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, cells)| cells.len() != cols)
+        {
+            return Err(BoardError::InvalidBoard(format!(
+                "row {} has {} cells, but the first row has {}",
+                row,
+                cells.len(),
+                cols
+            )));
+        }
+
+        // Tokens fall until they land on a token, a blocked cell or the bottom of the board
+        for (row, pair) in rows.windows(2).enumerate() {
+            let floating = (0..cols).find(|&col| {
+                pair[0][col].is_some()
+                    && pair[1][col].is_none()
+                    && !blocked.contains(&(row + 1, col))
+            });
+            if let Some(col) = floating {
+                return Err(BoardError::InvalidBoard(format!(
+                    "the token at column {}, row {} floats above an empty cell",
+                    col, row
+                )));
             }
+        }
+
+        Ok(Self {
+            rows,
+            gravity: Gravity::Down,
+            blocked,
+            wrap: false,
+            max_height: None,
+            ban_first_center: false,
+            last_placed: None,
+            ghost: None,
+        })
+    }
+
+    /// Splits a line of text into the symbol of each cell.
+    ///
+    /// Empty cells are returned as empty strings.
+    fn parse_row(line: &str) -> Vec<String> {
+        if !line.trim_start().starts_with('[') {
+            return line
+                .chars()
+                .map(|symbol| symbol.to_string().trim().to_string())
+                .collect();
+        }
+
+        // Rows in the format of `display`, where each cell is wrapped in brackets
+        line.split(']')
+            .filter_map(|cell| cell.trim_start().strip_prefix('['))
+            .map(|cell| cell.trim().to_string())
+            .collect()
+    }
+
+    /// Returns whether players choose a row rather than a column.
+    pub fn is_sideways(&self) -> bool {
+        matches!(self.gravity, Gravity::Left | Gravity::Right)
+    }
+
+    /// Returns the number of lanes tokens can be dropped into.
+    pub fn lane_count(&self) -> usize {
+        if self.is_sideways() {
+            self.rows.len()
+        } else {
+            self.rows[0].len()
+        }
+    }
+
+    /// Returns the number of cells in each lane.
+    fn lane_length(&self) -> usize {
+        if self.is_sideways() {
+            self.rows[0].len()
+        } else {
+            self.rows.len()
+        }
+    }
+
+    /// Returns how deep tokens can be stacked in each lane, taking `max_height` into account.
+    fn lane_capacity(&self) -> usize {
+        let length = self.lane_length();
+        self.max_height.map_or(length, |height| height.min(length))
+    }
+
+    /// Returns the (row, column) of the cell `depth` cells away from the wall tokens fall towards.
+    fn lane_cell(&self, lane: usize, depth: usize) -> (usize, usize) {
+        match self.gravity {
+            Gravity::Down => (self.rows.len() - depth - 1, lane),
+            Gravity::Up => (depth, lane),
+            Gravity::Left => (lane, depth),
+            Gravity::Right => (lane, self.rows[0].len() - depth - 1),
+        }
+    }
+
+    /// Returns the (row, column) of every cell in the lane, starting at the wall tokens fall towards.
+    fn lane_cells(&self, lane: usize) -> Vec<(usize, usize)> {
+        (0..self.lane_length())
+            .map(|depth| self.lane_cell(lane, depth))
+            .collect()
+    }
+
+    /// Empties every cell, keeping the size of the board, its gravity and its blocked cells.
+    ///
+    /// The rows are cleared in place, so no memory is allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let mut board = Board::new(3, 4);
+    /// board.place_token(1, Player::with_token("Alice", 'a')).unwrap();
+    /// board.reset();
+    ///
+    /// assert_eq!(board.rows.len(), 3);
+    /// assert!(board.rows.iter().all(|row| row.len() == 4));
+    /// assert!(board.iter_cells().all(|(_, _, cell)| cell.is_none()));
+    /// ```
+    pub fn reset(&mut self) {
+        for cell in self.rows.iter_mut().flatten() {
+            *cell = None;
+        }
+        self.last_placed = None;
+        self.ghost = None;
+    }
+
+    /// Returns the cell at (row, column), or None if it is outside the board.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let player = Player::with_token("Alice", 'a');
+    /// let mut board = Board::new(2, 2);
+    /// board.place_token(0, player.clone()).unwrap();
+    ///
+    /// assert_eq!(board.get(1, 0), Some(&Some(player)));
+    /// assert_eq!(board.get(0, 0), Some(&None));
+    /// assert_eq!(board.get(2, 0), None);
+    /// ```
+    pub fn get(&self, row: usize, col: usize) -> Option<&BoardCell> {
+        self.rows.get(row).and_then(|cells| cells.get(col))
+    }
+
+    /// Returns the cell at (row, column) for editing, or None if it is outside the board.
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut BoardCell> {
+        self.rows.get_mut(row).and_then(|cells| cells.get_mut(col))
+    }
+
+    /// Returns whether the cell at (row, column) is blocked.
+    pub fn is_blocked(&self, row: usize, col: usize) -> bool {
+        self.blocked.contains(&(row, col))
+    }
+
+    /// Returns the number of cells below the spot the next token dropped into the lane lands in.
+    ///
+    /// With downward gravity this is the height of the column. Tokens come to rest on the
+    /// token or blocked cell farthest from the wall, so any empty cells below it count too.
+    ///
+    /// Cells can be edited directly through `rows` and `blocked`, so heights aren't kept
+    /// between calls: the lane is scanned from its open end, which takes up to one step per
+    /// cell of the lane.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a')];
+    /// let mut board = Board::from_str("  \n# \n  ", &players).unwrap();
+    ///
+    /// // The token rests on the blocked cell, leaving the cell below it out of reach
+    /// assert_eq!(board.column_height(0), 2);
+    /// assert_eq!(board.place_token(0, players[0].clone()), Ok((0, 0)));
+    /// assert!(!board.valid_move(0));
+    /// ```
+    pub fn column_height(&self, lane: usize) -> usize {
+        (0..self.lane_length())
+            .rev()
+            .find(|&depth| {
+                let (row, col) = self.lane_cell(lane, depth);
+                self.rows[row][col].is_some() || self.is_blocked(row, col)
+            })
+            .map_or(0, |depth| depth + 1)
+    }
 
-            output.push('\n');
+    /// Returns the (row, column) of the token farthest from the wall in the lane.
+    ///
+    /// This is the most recently placed token of the lane. Returns None if the lane is empty or
+    /// the last dropped token rests on a blocked cell.
+    pub fn lane_top(&self, lane: usize) -> Option<(usize, usize)> {
+        match self.column_height(lane) {
+            0 => None,
+            height => Some(self.lane_cell(lane, height - 1))
+                .filter(|&(row, col)| self.rows[row][col].is_some()),
         }
+    }
+
+    /// Returns the cells a token passes through when it falls into (row, column).
+    ///
+    /// The path starts at the edge tokens are dropped in from and ends at (row, column).
+    pub fn drop_path(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut path: Vec<(usize, usize)> = self
+            .lane_cells(self.lane_of(row, col))
+            .into_iter()
+            .rev()
+            .take_while(|&cell| cell != (row, col))
+            .collect();
+        path.push((row, col));
+        path
+    }
+
+    /// Returns the lane that contains the cell at (row, column).
+    pub fn lane_of(&self, row: usize, col: usize) -> usize {
+        if self.is_sideways() {
+            row
+        } else {
+            col
+        }
+    }
+
+    /// Renders a line with a `v` marker above the selected column.
+    ///
+    /// Lines up with the output of `display_with` when printed directly above it with the same
+    /// `settings`.
+    pub fn column_marker(&self, selected: usize, settings: &DisplaySettings) -> String {
+        settings
+            .style
+            .renderer()
+            .column_marker(self, selected, settings)
+    }
+
+    /// Returns every cell of the board with its position, as `(row, column, cell)`.
+    ///
+    /// Cells are visited row by row, from the top left.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let mut board = Board::new(2, 3);
+    /// board.place_token(1, Player::with_token("Alice", 'a')).unwrap();
+    ///
+    /// let taken: Vec<(usize, usize)> = board
+    ///     .iter_cells()
+    ///     .filter(|(_, _, cell)| cell.is_some())
+    ///     .map(|(row, col, _)| (row, col))
+    ///     .collect();
+    /// assert_eq!(taken, vec![(1, 1)]);
+    /// assert_eq!(board.iter_cells().count(), 6);
+    /// ```
+    pub fn iter_cells(&self) -> impl Iterator<Item = (usize, usize, &BoardCell)> {
+        self.rows.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .map(move |(col, cell)| (row, col, cell))
+        })
+    }
+
+    /// Displays the game board with the default `DisplaySettings`.
+    ///
+    /// The token placed last is marked, e.g. `(a)` instead of `[a]`, so the latest move stands
+    /// out.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// # colored::control::set_override(false);
+    /// let player = Player::with_token("Alice", 'a');
+    /// let mut board = Board::new(2, 3);
+    /// board.place_token(0, player.clone()).unwrap();
+    /// board.place_token(1, player).unwrap();
+    ///
+    /// assert!(board.display().ends_with("[ ][ ][ ]\n[a](a)[ ]\n"));
+    /// ```
+    ///
+    /// With sideways gravity each row is prefixed with its index so players can pick a lane.
+    pub fn display(&self) -> String {
+        self.display_with(&DisplaySettings::default())
+    }
 
-        output
+    /// Displays the game board in the style, cell width and labels of `settings`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::game::render::DisplaySettings;
+    /// # use connect_four::game::util::{ColumnLabels, Labels};
+    /// let settings = DisplaySettings {
+    ///     labels: Labels::new(true, ColumnLabels::Numeric, 3),
+    ///     ..DisplaySettings::default()
+    /// };
+    ///
+    /// assert!(Board::new(1, 3).display_with(&settings).starts_with("1  2  3  \n"));
+    /// ```
+    pub fn display_with(&self, settings: &DisplaySettings) -> String {
+        settings.style.renderer().render(self, settings)
     }
 
     /// Places a token on the game board in the specified column.
-    pub fn place_token(&mut self, col: usize, player: Player) {
-        let mut target_row = 0;
+    ///
+    /// With sideways gravity `col` selects a row instead. The token falls towards the wall set by
+    /// the board's gravity.
+    ///
+    /// Returns the (row, column) the token landed in. The landing cell is worked out from
+    /// `column_height`, so placing takes as long as finding the height of the lane.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let player = Player::with_token("Alice", 'a');
+    /// let mut board = Board::new(3, 2);
+    /// board.place_token(1, player.clone()).unwrap();
+    /// board.place_token(1, player.clone()).unwrap();
+    ///
+    /// // Only the top row is still free
+    /// assert_eq!(board.place_token(1, player.clone()), Ok((0, 1)));
+    /// assert!(board.place_token(1, player).is_err());
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// - If the column is out of range.
+    /// - If the column is full.
+    pub fn place_token(
+        &mut self,
+        col: usize,
+        player: Player,
+    ) -> Result<(usize, usize), BoardError> {
+        if col >= self.lane_count() {
+            return Err(BoardError::ColumnOutOfRange(col));
+        }
 
-        for (i, row) in self.rows.iter().enumerate() {
-            if row[col].is_none() {
-                target_row = i;
-            }
+        // The token lands on top of the stack, e.g. in row `rows - height - 1` with downward gravity
+        let height = self.column_height(col);
+        if height >= self.lane_capacity() {
+            return Err(BoardError::ColumnFull(col));
+        }
+        if self.is_banned(col) {
+            return Err(BoardError::CenterBanned(col));
+        }
+
+        let (row, target_col) = self.lane_cell(col, height);
+        self.rows[row][target_col] = Some(player);
+        self.last_placed = Some((row, target_col));
+
+        Ok((row, target_col))
+    }
+
+    /// Returns a copy of the board showing where the player's token would land in `col`,
+    /// without placing it on this board.
+    ///
+    /// The token is drawn faintly and in braces, e.g. `{a}`. Returns None if the column can't be
+    /// played.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// # colored::control::set_override(false);
+    /// let player = Player::with_token("Alice", 'a');
+    /// let mut board = Board::new(2, 3);
+    /// board.place_token(1, player.clone()).unwrap();
+    ///
+    /// let preview = board.preview(1, &player).unwrap();
+    /// assert_eq!(preview.ghost, Some((0, 1)));
+    /// assert!(preview.display().ends_with("[ ]{a}[ ]\n[ ](a)[ ]\n"));
+    /// assert!(board.rows[0][1].is_none());
+    /// ```
+    pub fn preview(&self, col: usize, player: &Player) -> Option<Board> {
+        let mut board = self.clone();
+        let cell = board.place_token(col, player.clone()).ok()?;
+        board.last_placed = self.last_placed;
+        board.ghost = Some(cell);
+        Some(board)
+    }
+
+    /// Places a token in the cell at (row, column), regardless of gravity.
+    ///
+    /// ## Errors
+    ///
+    /// - If the cell is out of range.
+    /// - If the cell already holds a token.
+    pub fn place_at(&mut self, row: usize, col: usize, player: Player) -> Result<(), BoardError> {
+        if self.is_blocked(row, col) {
+            return Err(BoardError::CellBlocked(row, col));
+        }
+
+        let cell = self
+            .get_mut(row, col)
+            .ok_or(BoardError::CellOutOfRange(row, col))?;
+        if cell.is_some() {
+            return Err(BoardError::CellOccupied(row, col));
+        }
+
+        *cell = Some(player);
+        self.last_placed = Some((row, col));
+        Ok(())
+    }
+
+    /// Removes the player's token from the bottom of the column, sliding the tokens above it down.
+    ///
+    /// With other gravity directions the token is taken from the wall tokens fall towards.
+    ///
+    /// ## Errors
+    ///
+    /// - If the column is out of range.
+    /// - If the bottom cell is empty or holds another player's token.
+    pub fn pop_out(&mut self, col: usize, player: &Player) -> Result<(), BoardError> {
+        if col >= self.lane_count() {
+            return Err(BoardError::ColumnOutOfRange(col));
+        }
+
+        let cells = self.lane_cells(col);
+        let (bottom_row, bottom_col) = cells[0];
+        if self.rows[bottom_row][bottom_col].as_ref() != Some(player) {
+            return Err(BoardError::NotYourToken(col));
+        }
+
+        // Shift every cell in the lane one step towards the wall, up to the first blocked cell
+        let open = cells
+            .iter()
+            .take_while(|&&(row, col)| !self.is_blocked(row, col))
+            .count();
+        for pair in cells[..open].windows(2) {
+            let (to_row, to_col) = pair[0];
+            let (from_row, from_col) = pair[1];
+            self.rows[to_row][to_col] = self.rows[from_row][from_col].take();
         }
+        self.last_placed = None;
 
-        self.rows[target_row][col] = Some(player);
+        Ok(())
+    }
+
+    /// Returns the columns whose bottom token belongs to the player, i.e. where they may pop out.
+    ///
+    /// With other gravity directions the token at the wall tokens fall towards is checked.
+    pub fn pop_out_lanes(&self, player: &Player) -> Vec<usize> {
+        (0..self.lane_count())
+            .filter(|&lane| {
+                let (row, col) = self.lane_cells(lane)[0];
+                self.rows[row][col].as_ref() == Some(player)
+            })
+            .collect()
     }
 
     /// Returns diagonal rows from top right to bottom left.
@@ -68,6 +919,7 @@ impl Board {
     /// ## Example
     ///
     /// ```
+    /// # use connect_four::game::board::Board;
     /// let board = Board::new(3, 3);
     /// ```
     ///
@@ -96,9 +948,8 @@ impl Board {
         for d in 0..(rows + cols - 1) {
             let mut diagonal = Vec::new();
             for i in 0..rows {
-                let j = (cols as isize - 1) - (d as isize - i as isize);
-                if j >= 0 && (j as usize) < cols {
-                    diagonal.push(self.rows[i][j as usize].clone());
+                if let Some(cell) = (cols - 1 + i).checked_sub(d).and_then(|j| self.get(i, j)) {
+                    diagonal.push(cell.clone());
                 }
             }
             if !diagonal.is_empty() {
@@ -114,6 +965,7 @@ impl Board {
     /// ## Example
     ///
     /// ```
+    /// # use connect_four::game::board::Board;
     /// let board = Board::new(3, 3);
     /// ```
     ///
@@ -142,9 +994,8 @@ impl Board {
         for d in 0..(rows + cols - 1) {
             let mut diagonal = Vec::new();
             for i in 0..rows {
-                let j = d as isize - i as isize;
-                if j >= 0 && (j as usize) < cols {
-                    diagonal.push(self.rows[i][j as usize].clone());
+                if let Some(cell) = d.checked_sub(i).and_then(|j| self.get(i, j)) {
+                    diagonal.push(cell.clone());
                 }
             }
             if !diagonal.is_empty() {
@@ -155,24 +1006,580 @@ impl Board {
         diagonals
     }
 
+    /// Returns every row, column and diagonal of the board.
+    pub fn lines(&self) -> Vec<Vec<BoardCell>> {
+        let mut lines = self.rows.clone();
+
+        for col in 0..self.rows[0].len() {
+            lines.push(
+                (0..self.rows.len())
+                    .filter_map(|row| self.get(row, col).cloned())
+                    .collect(),
+            );
+        }
+
+        lines.extend(self.get_diagonals_top_left_to_bottom_right());
+        lines.extend(self.get_diagonals_top_right_to_bottom_left());
+        lines
+    }
+
+    /// Returns the (row, column) of every cell in every row, column and diagonal of the board.
+    ///
+    /// Lines are listed in the same order as by `lines`. On a board that wraps around, the
+    /// diagonals continue across the left and right edges instead, and each row is a ring whose
+    /// last cell is next to its first (see `is_ring`).
+    pub fn line_positions(&self) -> Vec<Vec<(usize, usize)>> {
+        let rows = self.rows.len();
+        let cols = self.rows[0].len();
+
+        let mut lines: Vec<Vec<(usize, usize)>> = (0..rows)
+            .map(|row| (0..cols).map(|col| (row, col)).collect())
+            .collect();
+
+        for col in 0..cols {
+            lines.push((0..rows).map(|row| (row, col)).collect());
+        }
+
+        if self.wrap {
+            // Every column starts one diagonal in each direction
+            for start in 0..cols {
+                lines.push(
+                    (0..rows)
+                        .map(|row| (row, (start + cols - row % cols) % cols))
+                        .collect(),
+                );
+            }
+            for start in 0..cols {
+                lines.push((0..rows).map(|row| (row, (start + row) % cols)).collect());
+            }
+
+            return lines;
+        }
+
+        for d in 0..(rows + cols - 1) {
+            lines.push(
+                (0..rows)
+                    .filter_map(|i| d.checked_sub(i).filter(|&j| j < cols).map(|j| (i, j)))
+                    .collect(),
+            );
+        }
+
+        for d in 0..(rows + cols - 1) {
+            lines.push(
+                (0..rows)
+                    .filter_map(|i| {
+                        (cols - 1 + i)
+                            .checked_sub(d)
+                            .filter(|&j| j < cols)
+                            .map(|j| (i, j))
+                    })
+                    .collect(),
+            );
+        }
+
+        lines
+    }
+
+    /// Returns whether the line is a whole row of a board that wraps around, so its last cell is
+    /// next to its first.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let mut game = Game::new(3, 6, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.board = Board::from_str("      \n      \naa b a", &game.players).unwrap();
+    /// assert_eq!(game.peek_move(4), Ok(GameStatus::Ongoing));
+    ///
+    /// // With the edges joined, the bottom row continues from column 5 to column 0
+    /// game.board.wrap = true;
+    /// assert!(game.board.is_ring(&game.board.line_positions()[2]));
+    /// assert_eq!(game.play_move(4), Ok(GameStatus::Win(game.players[0].clone())));
+    /// ```
+    pub fn is_ring(&self, line: &[(usize, usize)]) -> bool {
+        self.wrap
+            && line.len() == self.rows[0].len()
+            && line.iter().all(|&(row, _)| row == line[0].0)
+    }
+
+    /// Returns the (row, column) of every cell in every row, column and diagonal long enough to
+    /// hold `tokens_to_win` tokens.
+    ///
+    /// Each line runs from edge to edge, in the same order as `line_positions`, and lines too
+    /// short to ever hold a win are left out. The lines are computed on every call, so callers
+    /// that need them often keep them in a `LineCache`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// let board = Board::new(3, 3);
+    ///
+    /// // 3 rows, 3 columns and the 2 corner to corner diagonals
+    /// assert_eq!(board.all_lines(3).len(), 8);
+    /// // The 4 diagonals of two cells count as well
+    /// assert_eq!(board.all_lines(2).len(), 12);
+    /// assert!(board.all_lines(3).contains(&vec![(0, 0), (1, 1), (2, 2)]));
+    /// ```
+    pub fn all_lines(&self, tokens_to_win: usize) -> Vec<Vec<(usize, usize)>> {
+        self.line_positions()
+            .into_iter()
+            .filter(|line| line.len() >= tokens_to_win)
+            .collect()
+    }
+
+    /// Checks whether any player can still complete a line of `tokens_to_win` tokens.
+    ///
+    /// A stretch of `tokens_to_win` cells is winnable for a player when every token in it is
+    /// theirs or a teammate's. Stretches holding tokens of two opposing players are dead for
+    /// everyone.
+    pub fn any_line_still_winnable(&self, tokens_to_win: usize, players: &[Player]) -> bool {
+        self.any_of_lines_still_winnable(&self.all_lines(tokens_to_win), tokens_to_win, players)
+    }
+
+    /// Checks whether any player can still complete a line of `tokens_to_win` tokens in `lines`,
+    /// which are the lines returned by `all_lines`.
+    pub(crate) fn any_of_lines_still_winnable(
+        &self,
+        lines: &[Vec<(usize, usize)>],
+        tokens_to_win: usize,
+        players: &[Player],
+    ) -> bool {
+        lines.iter().any(|line| {
+            // Stretches of a ring may run past its end and continue at its start
+            let mut line = line.clone();
+            if self.is_ring(&line) {
+                line.extend_from_within(..tokens_to_win - 1);
+            }
+
+            line.windows(tokens_to_win).any(|window| {
+                players.iter().any(|player| {
+                    window.iter().all(|&(row, col)| match &self.rows[row][col] {
+                        Some(owner) => owner.is_teammate(player),
+                        None => !self.is_blocked(row, col),
+                    })
+                })
+            })
+        })
+    }
+
     /// Checks if the column is full.
+    ///
+    /// A column is full once it is stacked up to `max_height`, even if cells above are empty.
+    /// With sideways gravity `col` selects a row instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::{Board, BoardError};
+    /// # use connect_four::Player;
+    /// let player = Player::with_token("Alice", 'a');
+    /// let mut board = Board::new(6, 7);
+    /// board.max_height = Some(2);
+    /// board.place_token(3, player.clone()).unwrap();
+    /// board.place_token(3, player.clone()).unwrap();
+    ///
+    /// assert!(!board.valid_move(3));
+    /// assert_eq!(board.place_token(3, player), Err(BoardError::ColumnFull(3)));
+    /// assert!(board.rows[3][3].is_none());
+    /// ```
     pub fn valid_move(&self, col: usize) -> bool {
-        if col >= self.rows[0].len() {
+        if col >= self.lane_count() || self.is_banned(col) {
             return false;
         }
 
         // check if column is full
-        self.rows.iter().any(|row| row[col].is_none())
+        self.column_height(col) < self.lane_capacity()
+    }
+
+    /// Checks if `col` may not be played because it is in the center while the center is
+    /// banned, see `ban_first_center`.
+    ///
+    /// Boards with one or two columns have no other column to open with, so nothing is banned.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::{Board, BoardError};
+    /// # use connect_four::Player;
+    /// let player = Player::with_token("Alice", 'a');
+    /// let mut board = Board::new(6, 8);
+    /// board.ban_first_center = true;
+    ///
+    /// // Both center columns are banned on the first move
+    /// assert!(!board.valid_move(3) && !board.valid_move(4));
+    /// assert_eq!(board.place_token(4, player.clone()), Err(BoardError::CenterBanned(4)));
+    /// assert_eq!(board.open_columns(), vec![0, 1, 2, 5, 6, 7]);
+    ///
+    /// // And allowed once the ban is lifted
+    /// board.place_token(0, player.clone()).unwrap();
+    /// board.ban_first_center = false;
+    /// assert!(board.valid_move(3) && board.valid_move(4));
+    /// ```
+    pub fn is_banned(&self, col: usize) -> bool {
+        let lanes = self.lane_count();
+
+        self.ban_first_center && lanes > 2 && (2 * col).abs_diff(lanes - 1) <= 1
+    }
+
+    /// Returns every column a token can still be dropped into, in order.
+    ///
+    /// These are exactly the columns for which `valid_move` holds, so callers can list legal
+    /// moves without trying each one. With sideways gravity the open rows are returned instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let board = Board::from_str("a  \nb a\nabb", &players).unwrap();
+    ///
+    /// assert_eq!(board.open_columns(), vec![1, 2]);
+    /// ```
+    pub fn open_columns(&self) -> Vec<usize> {
+        (0..self.lane_count())
+            .filter(|&col| self.valid_move(col))
+            .collect()
     }
 
     /// Checks to see if there are any more moves left.
     pub fn is_board_full(&self) -> bool {
+        self.open_columns().is_empty()
+    }
+
+    /// Returns whether any cell is neither taken nor blocked.
+    ///
+    /// Unlike `is_board_full` this includes cells a dropped token can't reach, such as cells
+    /// underneath a blocked cell.
+    pub fn has_empty_cell(&self) -> bool {
+        self.iter_cells()
+            .any(|(row, col, cell)| cell.is_none() && !self.is_blocked(row, col))
+    }
+
+    /// Infers whose turn it is from the tokens on the board, e.g. for a loaded game or a puzzle.
+    ///
+    /// Players take turns, so the player who placed the fewest tokens is due to move. Ties go to
+    /// the player listed first. Tokens are matched to `players` by their symbol. Returns None if
+    /// there are no players.
+    ///
+    /// Handicaps and pop outs change how many tokens a player has, so the result only holds for
+    /// games played by dropping tokens in turn.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    ///
+    /// let board = Board::from_str("   \n   \nab ", &players).unwrap();
+    /// assert_eq!(board.infer_next_player(&players), Some(0));
+    ///
+    /// let board = Board::from_str("   \na  \nab ", &players).unwrap();
+    /// assert_eq!(board.infer_next_player(&players), Some(1));
+    /// assert_eq!(board.infer_next_player(&[]), None);
+    /// ```
+    pub fn infer_next_player(&self, players: &[Player]) -> Option<usize> {
+        let mut counts = vec![0; players.len()];
+        for token in self.rows.iter().flatten().flatten() {
+            if let Some(index) = players
+                .iter()
+                .position(|player| player.token == token.token)
+            {
+                counts[index] += 1;
+            }
+        }
+
+        // min_by_key keeps the first of equal counts, i.e. the player listed first
+        counts
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, count)| count)
+            .map(|(index, _)| index)
+    }
+
+    /// Returns a copy of the board turned upside down, i.e. rotated by 180 degrees.
+    ///
+    /// Gravity is turned around along with the board, so every token still rests on the same
+    /// neighbor. The original board is left unchanged.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::{Board, Gravity};
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let board = Board::from_compact(".../a../ab.", &players).unwrap();
+    /// let rotated = board.rotated_180();
+    ///
+    /// assert_eq!(rotated.to_compact(), ".ba/..a/...");
+    /// assert_eq!(rotated.gravity, Gravity::Up);
+    /// assert_eq!(rotated.rotated_180().to_compact(), board.to_compact());
+    /// ```
+    pub fn rotated_180(&self) -> Board {
+        let (rows, cols) = (self.rows.len(), self.rows[0].len());
+        let mut board = self.mapped(|row, col| (rows - 1 - row, cols - 1 - col));
+        board.gravity = match self.gravity {
+            Gravity::Down => Gravity::Up,
+            Gravity::Up => Gravity::Down,
+            Gravity::Left => Gravity::Right,
+            Gravity::Right => Gravity::Left,
+        };
+        board
+    }
+
+    /// Returns a copy of the board mirrored from left to right.
+    ///
+    /// Sideways gravity is mirrored along with the board. The original board is left unchanged.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// # use connect_four::game::WinDirections;
+    /// let mut game = Game::new(3, 5, 3, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.win_directions = WinDirections::HORIZONTAL;
+    /// game.board = Board::from_compact("...../...../aaab.", &game.players).unwrap();
+    /// assert_eq!(game.status(), GameStatus::Win(game.players[0].clone()));
+    ///
+    /// // A horizontal win is still a horizontal win in the mirror image
+    /// game.board = game.board.mirrored_horizontal();
+    /// assert_eq!(game.board.to_compact(), "...../...../.baaa");
+    /// assert_eq!(game.status(), GameStatus::Win(game.players[0].clone()));
+    /// ```
+    pub fn mirrored_horizontal(&self) -> Board {
+        let cols = self.rows[0].len();
+        let mut board = self.mapped(|row, col| (row, cols - 1 - col));
+        board.gravity = match self.gravity {
+            Gravity::Left => Gravity::Right,
+            Gravity::Right => Gravity::Left,
+            gravity => gravity,
+        };
+        board
+    }
+
+    /// Returns the symmetric version of the board whose compact form sorts first.
+    ///
+    /// Positions that are mirror images of each other play the same, so they share a canonical
+    /// form, e.g. to look them up in an opening book once. Only the mirror image across the
+    /// direction tokens fall in is considered, since turning the board around would turn its
+    /// gravity around too.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::Player;
+    /// let players = vec![Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b')];
+    /// let left = Board::from_compact(".../ab.", &players).unwrap();
+    /// let right = Board::from_compact(".../.ba", &players).unwrap();
+    ///
+    /// assert_eq!(left.canonical().to_compact(), ".../.ba");
+    /// assert_eq!(right.canonical().to_compact(), ".../.ba");
+    /// ```
+    pub fn canonical(&self) -> Board {
+        // With sideways gravity the board is mirrored from top to bottom instead
+        let mirrored = if self.is_sideways() {
+            self.rotated_180().mirrored_horizontal()
+        } else {
+            self.mirrored_horizontal()
+        };
+
+        if mirrored.to_compact() < self.to_compact() {
+            mirrored
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Returns a copy of the board with every cell moved to the position given by `to`.
+    fn mapped(&self, to: impl Fn(usize, usize) -> (usize, usize)) -> Board {
+        let mut board = self.clone();
+
+        for (row, col, cell) in self.iter_cells() {
+            let (to_row, to_col) = to(row, col);
+            board.rows[to_row][to_col] = cell.clone();
+        }
+        board.blocked = self
+            .blocked
+            .iter()
+            .map(|&(row, col)| to(row, col))
+            .collect();
+        board.last_placed = self.last_placed.map(|(row, col)| to(row, col));
+        board.ghost = self.ghost.map(|(row, col)| to(row, col));
+
+        board
+    }
+}
+
+/// Boards are equal if the same cells hold tokens of the same players, compared by token, and
+/// the same cells are blocked.
+///
+/// Everything else, such as the last token placed or the rules the board is played with, is
+/// left out, so boards compare as positions, e.g. to look them up in an opening book.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::board::Board;
+/// # use connect_four::Player;
+/// # use std::collections::hash_map::DefaultHasher;
+/// # use std::hash::{Hash, Hasher};
+/// fn hash(board: &Board) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     board.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let (alice, bob) = (Player::with_token("Alice", 'a'), Player::with_token("Bob", 'b'));
+/// let mut first = Board::new(6, 7);
+/// first.place_token(3, alice.clone()).unwrap();
+/// first.place_token(4, bob.clone()).unwrap();
+///
+/// // The same position reached by another path, with differently named players
+/// let mut second = Board::new(6, 7);
+/// second.place_token(4, Player::with_token("Robert", 'b')).unwrap();
+/// second.place_token(3, alice.clone()).unwrap();
+/// assert!(first == second);
+/// assert_eq!(hash(&first), hash(&second));
+///
+/// second.place_token(0, bob).unwrap();
+/// assert!(first != second);
+/// ```
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.blocked == other.blocked
+            && self.rows.len() == other.rows.len()
+            && self.rows.iter().zip(&other.rows).all(|(row, other_row)| {
+                row.len() == other_row.len()
+                    && row.iter().zip(other_row).all(|(cell, other_cell)| {
+                        cell.as_ref().map(|player| &player.token)
+                            == other_cell.as_ref().map(|player| &player.token)
+                    })
+            })
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.blocked.hash(state);
+        self.rows.len().hash(state);
         for row in &self.rows {
-            if row.iter().any(|cell| cell.is_none()) {
-                return false;
+            row.len().hash(state);
+            for cell in row {
+                cell.as_ref().map(|player| &player.token).hash(state);
             }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-        true
+    /// Drops a token into each of `lanes` in turn, and returns where each one landed.
+    fn drop_all(board: &mut Board, lanes: &[usize]) -> Vec<(usize, usize)> {
+        let alice = Player::with_token("Alice", 'a');
+        lanes
+            .iter()
+            .map(|&lane| board.place_token(lane, alice.clone()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn tokens_fall_towards_the_wall_of_the_gravity() {
+        let cases = [
+            (Gravity::Down, vec![(2, 1), (1, 1)]),
+            (Gravity::Up, vec![(0, 1), (1, 1)]),
+            (Gravity::Left, vec![(1, 0), (1, 1)]),
+            (Gravity::Right, vec![(1, 3), (1, 2)]),
+        ];
+
+        for (gravity, landed) in cases {
+            let mut board = Board::new(3, 4);
+            board.gravity = gravity;
+            assert_eq!(drop_all(&mut board, &[1, 1]), landed, "{:?}", gravity);
+        }
+    }
+
+    #[test]
+    fn sideways_lanes_are_rows() {
+        for gravity in [Gravity::Left, Gravity::Right] {
+            let mut board = Board::new(2, 3);
+            board.gravity = gravity;
+
+            assert_eq!(board.lane_count(), 2);
+            assert!(!board.valid_move(2));
+            assert_eq!(
+                board.place_token(2, Player::with_token("Alice", 'a')),
+                Err(BoardError::ColumnOutOfRange(2))
+            );
+
+            drop_all(&mut board, &[0, 0, 0]);
+            assert!(!board.valid_move(0));
+            assert!(board.valid_move(1));
+            assert!(!board.is_board_full());
+
+            drop_all(&mut board, &[1, 1, 1]);
+            assert!(board.is_board_full(), "{:?}", gravity);
+        }
+    }
+
+    #[test]
+    fn a_board_with_upward_gravity_fills_up() {
+        let mut board = Board::new(2, 2);
+        board.gravity = Gravity::Up;
+
+        drop_all(&mut board, &[0, 0, 1]);
+        assert!(!board.valid_move(0));
+        assert!(!board.is_board_full());
+        drop_all(&mut board, &[1]);
+        assert!(board.is_board_full());
+    }
+
+    /// Returns the display columns at which the cells of a rendered row start.
+    fn cell_offsets(line: &str) -> Vec<usize> {
+        let mut offset = 0;
+        let mut offsets = Vec::new();
+        for c in line.chars() {
+            if c == '[' || c == '(' {
+                offsets.push(offset);
+            }
+            offset += UnicodeWidthChar::width(c).unwrap_or(0);
+        }
+        offsets
+    }
+
+    #[test]
+    fn emoji_tokens_keep_the_columns_aligned() {
+        let red = Player::with_token("Alice", "🔴");
+        let yellow = Player::with_token("Bob", "🟡");
+        let mut board = Board::new(3, 4);
+        for (col, player) in [(0, &red), (1, &yellow), (1, &red), (3, &yellow)] {
+            board.place_token(col, player.clone()).unwrap();
+        }
+
+        let display = board.display();
+        let lines: Vec<&str> = display.lines().collect();
+        let header = lines[0];
+        let labels: Vec<usize> = (0..4)
+            .map(|col| UnicodeWidthStr::width(&header[..header.find(&col.to_string()).unwrap()]))
+            .collect();
+
+        for line in &lines {
+            assert_eq!(
+                UnicodeWidthStr::width(*line),
+                UnicodeWidthStr::width(header)
+            );
+        }
+        for line in &lines[1..] {
+            assert_eq!(cell_offsets(line), labels, "{}", line);
+        }
     }
 }
@@ -0,0 +1,221 @@
+//! Cast module
+//!
+//! Contains the `CastOutput`, which records everything a game shows as an
+//! [asciinema v2](https://docs.asciinema.org/manual/asciicast/v2/) cast, so games can be shared
+//! and replayed with standard tools such as `asciinema play`.
+//!
+//! A cast is a header line followed by one line per piece of output, each stamped with the
+//! seconds since the recording started. Recordings are appended to, so the rounds of a match, or
+//! several sessions, end up in one cast that plays them back to back.
+
+use super::board::Board;
+use super::output::{describe_turn, Output};
+use super::render::DisplaySettings;
+use super::{GameStatus, Player};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The terminal size written to new casts when the real size is unknown.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Shows a game through another output while recording it as an asciinema v2 cast.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::board::Board;
+/// # use connect_four::game::cast::CastOutput;
+/// # use connect_four::game::output::{Output, QuietOutput};
+/// # use connect_four::game::render::DisplaySettings;
+/// let mut cast = CastOutput::new(Vec::new(), Box::new(QuietOutput::default()), 0.0);
+/// cast.write_header(80, 24).unwrap();
+/// cast.render_board(&Board::new(1, 2), &DisplaySettings::default());
+/// cast.show_message("Hello");
+///
+/// let cast = String::from_utf8(cast.into_writer()).unwrap();
+/// let lines: Vec<&str> = cast.lines().collect();
+/// assert!(lines[0].starts_with(r#"{"version": 2, "width": 80, "height": 24"#));
+/// assert!(lines[2].ends_with(r#", "o", "Hello\r\n"]"#));
+/// ```
+pub struct CastOutput<W: Write> {
+    /// The output the game is shown on.
+    inner: Box<dyn Output>,
+    /// Where the cast is written to.
+    writer: W,
+    /// When recording started.
+    started: Instant,
+    /// The seconds already recorded before, so appended output continues where it left off.
+    offset: f64,
+    /// Whether writing failed, after which recording stops.
+    failed: bool,
+}
+
+impl CastOutput<fs::File> {
+    /// Records to the cast at `path`, showing the game on `inner` as well.
+    ///
+    /// A new cast is started if the file does not exist or is empty, otherwise the recording is
+    /// appended after the last output of the cast.
+    ///
+    /// ## Errors
+    ///
+    /// - If the file can't be read or written.
+    pub fn append(path: &Path, inner: Box<dyn Output>) -> io::Result<Self> {
+        let existing = match fs::read_to_string(path) {
+            Ok(existing) => existing,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        // Every event starts with its time, e.g. `[1.5, "o", "..."]`
+        let offset = existing
+            .lines()
+            .rev()
+            .find_map(|line| {
+                line.strip_prefix('[')?
+                    .split(',')
+                    .next()?
+                    .trim()
+                    .parse()
+                    .ok()
+            })
+            .unwrap_or(0.0);
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut cast = Self::new(file, inner, offset);
+        if existing.trim().is_empty() {
+            let (width, height) = crossterm::terminal::size().unwrap_or(DEFAULT_SIZE);
+            cast.write_header(width, height)?;
+        }
+
+        Ok(cast)
+    }
+}
+
+impl<W: Write> CastOutput<W> {
+    /// Records to `writer`, showing the game on `inner` as well.
+    ///
+    /// Output is stamped starting from `offset` seconds. No header is written, see
+    /// `write_header`.
+    pub fn new(writer: W, inner: Box<dyn Output>, offset: f64) -> Self {
+        Self {
+            inner,
+            writer,
+            started: Instant::now(),
+            offset,
+            failed: false,
+        }
+    }
+
+    /// Writes the header that starts a cast, for a terminal of `width` by `height` characters.
+    ///
+    /// ## Errors
+    ///
+    /// - If writing fails.
+    pub fn write_header(&mut self, width: u16, height: u16) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+
+        writeln!(
+            self.writer,
+            r#"{{"version": 2, "width": {}, "height": {}, "timestamp": {}}}"#,
+            width, height, timestamp
+        )?;
+        self.writer.flush()
+    }
+
+    /// Returns the writer the cast was written to.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// Records `text` as output at the current time.
+    ///
+    /// Warns once if writing fails and stops recording, so a full disk doesn't end the game.
+    fn record(&mut self, text: &str) {
+        if self.failed {
+            return;
+        }
+
+        let time = self.offset + self.started.elapsed().as_secs_f64();
+        // Terminals need a carriage return to start lines at the left edge
+        let text = text.replace('\n', "\r\n");
+        let result = writeln!(self.writer, r#"[{:.6}, "o", "{}"]"#, time, escape(&text))
+            .and_then(|_| self.writer.flush());
+
+        if let Err(err) = result {
+            eprintln!("Warning: stopped recording the game: {}", err);
+            self.failed = true;
+        }
+    }
+}
+
+impl<W: Write> Output for CastOutput<W> {
+    fn clear(&mut self) {
+        self.inner.clear();
+        self.record("\x1B[2J\x1B[H");
+    }
+
+    fn render_board(&mut self, board: &Board, display: &DisplaySettings) {
+        self.inner.render_board(board, display);
+        self.record(&format!("{}\n", board.display_with(display)));
+    }
+
+    /// Frames are recorded as well, so the animation plays back.
+    fn render_frame(&mut self, board: &Board, display: &DisplaySettings) {
+        self.inner.render_frame(board, display);
+        self.record("\x1B[2J\x1B[H");
+        self.record(&format!("{}\n", board.display_with(display)));
+    }
+
+    fn show_message(&mut self, message: &str) {
+        self.inner.show_message(message);
+        self.record(&format!("{}\n", message));
+    }
+
+    fn show_error(&mut self, message: &str) {
+        self.inner.show_error(message);
+        self.record(&format!("{}\n", message));
+    }
+
+    /// The countdown is only shown, since it redraws what was recorded already.
+    fn update_countdown(&mut self, left: Duration) {
+        self.inner.update_countdown(left);
+    }
+
+    fn announce_turn(&mut self, player: &Player) {
+        self.inner.announce_turn(player);
+        self.record(&format!("{}'s ({}) Turn\n", player.name, player.token));
+    }
+
+    fn announce_move(&mut self, player: &Player, move_number: usize, round: Option<usize>) {
+        self.inner.announce_move(player, move_number, round);
+        self.record(&format!("{}\n", describe_turn(player, move_number, round)));
+    }
+
+    fn announce_result(&mut self, status: &GameStatus, message: &str) {
+        self.inner.announce_result(status, message);
+        self.record(&format!("{}\n", message));
+    }
+}
+
+/// Escapes `text` for use in a JSON string.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,312 @@
+//! Config module
+//!
+//! Contains the `Config` struct, which holds game settings read from a TOML file, and the
+//! environment variables that can set them too.
+//!
+//! Settings are layered, from highest to lowest precedence:
+//!
+//! 1. Options given on the command line.
+//! 2. Environment variables: `CONNECT_FOUR_ROWS`, `CONNECT_FOUR_COLS` and
+//!    `CONNECT_FOUR_TOKENS_TO_WIN`.
+//! 3. The config file. Without `--config`, `connect_four.toml` in the current directory is used
+//!    if it exists. Config files require the `config` feature, which is enabled by default.
+//! 4. The built-in defaults.
+//!
+//! ```toml
+//! rows = 6
+//! cols = 7
+//! tokens_to_win = 4
+//!
+//! [[players]]
+//! name = "Alice"
+//! token = "x"
+//!
+//! [[players]]
+//! name = "Computer"
+//! difficulty = "medium"
+//! handicap = [3]
+//! ```
+//!
+//! Players that only need a name and a token can also be listed compactly:
+//!
+//! ```toml
+//! players = ["Alice (x)", "Bob"]
+//! ```
+
+#[cfg(feature = "config")]
+use super::ai::Difficulty;
+#[cfg(feature = "config")]
+use super::player::AUTO_TOKEN;
+use super::roster::Roster;
+#[cfg(feature = "config")]
+use super::roster::RosterEntry;
+use super::util::Args;
+#[cfg(feature = "config")]
+use super::Player;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use std::env;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+/// The config file used when `--config` is not given.
+#[cfg(feature = "config")]
+pub const DEFAULT_CONFIG_FILE: &str = "connect_four.toml";
+
+/// Represents the settings of a config file. Every setting is optional.
+#[cfg(feature = "config")]
+#[derive(serde::Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The number of rows on the game board.
+    pub rows: Option<usize>,
+    /// The number of columns on the game board.
+    pub cols: Option<usize>,
+    /// The number of connected tokens required to win the game.
+    pub tokens_to_win: Option<usize>,
+    /// The players participating in the game.
+    ///
+    /// Each player is either a table or a string like `Alice (x)`.
+    #[serde(default, deserialize_with = "deserialize_players")]
+    pub players: Vec<PlayerConfig>,
+}
+
+/// Represents a player in a config file.
+#[cfg(feature = "config")]
+#[derive(serde::Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerConfig {
+    /// The name of the player.
+    pub name: String,
+    /// The token of the player. Assigned automatically when omitted.
+    pub token: Option<String>,
+    /// How strong the computer is for this player: `easy`, `medium` or `hard`.
+    /// Players with a difficulty are controlled by the computer.
+    pub difficulty: Option<Difficulty>,
+    /// The columns the player gets an extra token in before the game begins.
+    #[serde(default)]
+    pub handicap: Vec<usize>,
+}
+
+/// Represents a player in a config file, as a table or in compact form.
+#[cfg(feature = "config")]
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PlayerEntry {
+    /// A player like `Alice (x)`, see `Player::from_str`.
+    Compact(String),
+    /// A player with every setting.
+    Full(PlayerConfig),
+}
+
+/// Reads the players of a config file, parsing players given in compact form.
+#[cfg(feature = "config")]
+fn deserialize_players<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<PlayerConfig>, D::Error> {
+    let entries: Vec<PlayerEntry> = serde::Deserialize::deserialize(deserializer)?;
+
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            PlayerEntry::Compact(input) => {
+                let player: Player = input.parse().map_err(serde::de::Error::custom)?;
+                Ok(PlayerConfig {
+                    token: Some(player.token).filter(|token| token != AUTO_TOKEN),
+                    name: player.name,
+                    difficulty: None,
+                    handicap: Vec::new(),
+                })
+            }
+            PlayerEntry::Full(player) => Ok(player),
+        })
+        .collect()
+}
+
+#[cfg(feature = "config")]
+impl Config {
+    /// Reads a config file.
+    ///
+    /// ## Errors
+    ///
+    /// - If the file can't be read.
+    /// - If the file is not valid TOML or contains unknown settings.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let input =
+            std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        toml::from_str(&input).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Fills in every setting of `args` that was not given on the command line.
+    pub fn apply(self, args: &mut Args, matches: &ArgMatches) {
+        if let Some(rows) = self.rows.filter(|_| !from_command_line(matches, "rows")) {
+            args.rows = rows;
+        }
+        if let Some(cols) = self.cols.filter(|_| !from_command_line(matches, "cols")) {
+            args.cols = cols;
+        }
+        if let Some(tokens_to_win) = self
+            .tokens_to_win
+            .filter(|_| !from_command_line(matches, "tokens_to_win"))
+        {
+            args.tokens_to_win = tokens_to_win;
+        }
+
+        // Players on the command line or in a roster replace the players of the file entirely,
+        // along with their handicaps and difficulties
+        if self.players.is_empty()
+            || from_command_line(matches, "players")
+            || args.players_file.is_some()
+        {
+            return;
+        }
+
+        if !from_command_line(matches, "handicap") {
+            args.handicap = self
+                .players
+                .iter()
+                .flat_map(|player| {
+                    let name = &player.name;
+                    player.handicap.iter().map(move |&col| (name.clone(), col))
+                })
+                .collect();
+        }
+
+        // The players are set like those of a roster, which has the same fields but the handicap
+        let roster = Roster {
+            players: self
+                .players
+                .into_iter()
+                .map(|player| RosterEntry {
+                    name: player.name,
+                    token: player.token,
+                    difficulty: player.difficulty,
+                })
+                .collect(),
+        };
+        roster.apply(args);
+    }
+}
+
+/// Fills in every setting of `args` that was not given on the command line from the environment.
+///
+/// Malformed values are ignored with a warning.
+pub fn apply_env(args: &mut Args, matches: &ArgMatches) {
+    let settings: [(&str, &str, &mut usize); 3] = [
+        ("CONNECT_FOUR_ROWS", "rows", &mut args.rows),
+        ("CONNECT_FOUR_COLS", "cols", &mut args.cols),
+        (
+            "CONNECT_FOUR_TOKENS_TO_WIN",
+            "tokens_to_win",
+            &mut args.tokens_to_win,
+        ),
+    ];
+
+    for (var, id, setting) in settings {
+        if from_command_line(matches, id) {
+            continue;
+        }
+        let Ok(value) = env::var(var) else {
+            continue;
+        };
+
+        match value.trim().parse::<usize>() {
+            Ok(value) => *setting = value,
+            Err(_) => eprintln!("Warning: ignoring {}={:?}, expected a number", var, value),
+        }
+    }
+}
+
+/// Parses the command line and merges in the environment and the config file.
+///
+/// ## Errors
+///
+/// - If the config file given with `--config`, or the default config file, can't be loaded.
+/// - If the roster given with `--players-file` can't be loaded.
+pub fn parse_args() -> Result<Args, String> {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    #[cfg(feature = "config")]
+    {
+        let config = match &args.config {
+            Some(path) => Some(Config::load(path)?),
+            None => {
+                let path = Path::new(DEFAULT_CONFIG_FILE);
+                match path.exists() {
+                    true => Some(Config::load(path)?),
+                    false => None,
+                }
+            }
+        };
+
+        if let Some(config) = config {
+            config.apply(&mut args, &matches);
+        }
+    }
+    apply_env(&mut args, &matches);
+
+    if let Some(path) = args.players_file.clone() {
+        Roster::load(&path)?.apply(&mut args);
+    }
+
+    Ok(args)
+}
+
+/// Returns whether the argument with the given id was given on the command line.
+fn from_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+#[cfg(all(test, feature = "config"))]
+mod tests {
+    use super::*;
+
+    /// A config file with a handicap and a computer player.
+    const CONFIG: &str = r#"
+players = [
+    { name = "Alice", token = "x", handicap = [3] },
+    { name = "Computer", difficulty = "hard" },
+]
+"#;
+
+    /// Parses `command_line` like `parse_args`, without reading any files.
+    fn args(command_line: &[&str]) -> (Args, ArgMatches) {
+        let matches = Args::command()
+            .get_matches_from(std::iter::once("connect_four").chain(command_line.iter().copied()));
+        let args = Args::from_arg_matches(&matches).unwrap();
+        (args, matches)
+    }
+
+    #[test]
+    fn config_players_are_set_like_a_roster() {
+        let (mut from_config, matches) = args(&[]);
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        config.apply(&mut from_config, &matches);
+        let (mut from_roster, _) = args(&[]);
+        let roster = Roster::parse("Alice, x\nComputer, , hard").unwrap();
+        roster.apply(&mut from_roster);
+
+        assert_eq!(from_config.players, vec!["Alice", "Computer"]);
+        assert_eq!(from_config.tokens, vec!["x", AUTO_TOKEN]);
+        assert_eq!(from_config.bots, vec!["Computer"]);
+        assert_eq!(from_config.players, from_roster.players);
+        assert_eq!(from_config.tokens, from_roster.tokens);
+        assert_eq!(from_config.bots, from_roster.bots);
+        assert_eq!(from_config.bot_difficulties, from_roster.bot_difficulties);
+        assert_eq!(from_config.handicap, vec![("Alice".to_string(), 3)]);
+    }
+
+    #[test]
+    fn a_roster_replaces_the_config_players_with_their_handicaps() {
+        let (mut args, matches) = args(&["--players-file", "roster.txt"]);
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        config.apply(&mut args, &matches);
+        Roster::parse("Bob\nCarol").unwrap().apply(&mut args);
+
+        assert_eq!(args.players, vec!["Bob", "Carol"]);
+        assert!(args.tokens.is_empty());
+        assert!(args.handicap.is_empty());
+        assert!(args.bots.is_empty());
+    }
+}
@@ -0,0 +1,42 @@
+//! Event module
+//!
+//! Contains the `GameEvent` enum, describing what happens during a game.
+//!
+//! An observer registered with `Game::set_observer` receives every event as it happens. This
+//! decouples the game logic from how it is presented, e.g. for logging or spectating.
+
+use super::Player;
+
+/// A callback receiving the events of a game.
+pub type Observer = Box<dyn FnMut(&GameEvent)>;
+
+/// Represents something that happened during a game.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameEvent {
+    /// It is now the player's turn.
+    TurnStarted {
+        /// The player whose turn it is.
+        player: Player,
+    },
+    /// A player dropped a token, which landed at (row, col).
+    TokenPlaced {
+        /// The player who dropped the token.
+        player: Player,
+        /// The column the token landed in.
+        col: usize,
+        /// The row the token landed in.
+        row: usize,
+    },
+    /// A player completed a line and won the game.
+    WinDetected {
+        /// The winning player.
+        player: Player,
+    },
+    /// A player left the game, e.g. by forfeiting, and no longer takes turns.
+    PlayerEliminated {
+        /// The player who left.
+        player: Player,
+    },
+    /// The game ended in a draw.
+    Draw,
+}
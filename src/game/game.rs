@@ -7,13 +7,30 @@
 //! The `Game` struct is responsible for managing the game state, player turns, and game status.
 //!
 
-use crate::game::util;
+use crate::game::player::{AUTO_TOKEN, AUTO_TOKENS, MAX_TOKEN_WIDTH, PALETTE};
+use crate::game::util::Labels;
 use crate::Player;
-use std::{collections::HashSet, io};
+use colored::Color;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::io::{self, IsTerminal};
+use std::ops::BitOr;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use std::{fmt, thread};
 
-use super::board::{Board, BoardCell};
+use super::ai;
+use super::board::{Board, BoardCell, BoardError, LineCache};
+use super::event::{GameEvent, Observer};
+use super::input::{InputSource, StdinInput};
+use super::output::{Output, StdoutOutput};
+use super::render::DisplaySettings;
+use super::rng::Rng;
+use super::stats::GameStats;
+use super::strategy::{Command, Console, HumanStrategy, Strategy};
 
 /// Represents the status of a game.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameStatus {
     /// The game is still in progress.
     Ongoing,
@@ -23,7 +40,318 @@ pub enum GameStatus {
     Win(Player),
 }
 
+impl GameStatus {
+    /// Returns whether the game has ended, either in a win or a draw.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let mut game = Game::new(2, 2, 2, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// assert_eq!(game.play_move(0), Ok(GameStatus::Ongoing));
+    /// game.play_move(1).unwrap();
+    ///
+    /// let status = game.play_move(0).unwrap();
+    /// assert!(status.is_over());
+    /// assert_eq!(status, GameStatus::Win(game.players[0].clone()));
+    /// ```
+    pub fn is_over(&self) -> bool {
+        !matches!(self, Self::Ongoing)
+    }
+}
+
+/// Represents what happens when a player runs out of time on their turn.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeoutAction {
+    /// The player loses their turn.
+    #[default]
+    Skip,
+    /// A token is dropped into a random open column for the player.
+    Random,
+}
+
+/// Represents how a game is decided when the board fills up without a winner.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TieBreak {
+    /// The game is a draw.
+    #[default]
+    Draw,
+    /// The side with the longest run of tokens wins.
+    LongestLine,
+    /// The side with the most lines just one token short of winning wins.
+    Threats,
+}
+
+/// Represents where players may put their tokens.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placement {
+    /// Tokens are dropped into a column and fall as far as they can.
+    #[default]
+    Gravity,
+    /// Tokens can also be put in any empty cell, like a larger game of tic-tac-toe.
+    Free,
+}
+
+/// Represents the directions lines may win in, as a set of flags.
+///
+/// Flags are combined with `|`, e.g. `WinDirections::HORIZONTAL | WinDirections::VERTICAL`.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::board::Board;
+/// # use connect_four::game::WinDirections;
+/// # use connect_four::{Game, GameStatus, Player};
+/// let mut game = Game::new(4, 4, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+/// game.board = Board::from_str("   a\n  ab\n abb\nabbb", &game.players).unwrap();
+///
+/// assert!(matches!(game.status(), GameStatus::Win(_)));
+///
+/// game.win_directions = WinDirections::HORIZONTAL | WinDirections::VERTICAL;
+/// assert_eq!(game.status(), GameStatus::Ongoing);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WinDirections(u8);
+
+impl WinDirections {
+    /// Lines along a row.
+    pub const HORIZONTAL: Self = Self(1);
+    /// Lines along a column.
+    pub const VERTICAL: Self = Self(2);
+    /// Lines from the top left to the bottom right.
+    pub const DIAGONAL: Self = Self(4);
+    /// Lines from the top right to the bottom left.
+    pub const ANTI_DIAGONAL: Self = Self(8);
+    /// Lines in every direction, as in the standard game.
+    pub const ALL: Self = Self(15);
+
+    /// Returns whether every direction in `other` is in the set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether lines stepping (row_step, col_step) from cell to cell may win.
+    pub(crate) fn allows(self, row_step: isize, col_step: isize) -> bool {
+        let direction = match (row_step, col_step) {
+            (0, _) => Self::HORIZONTAL,
+            (_, 0) => Self::VERTICAL,
+            (row_step, col_step) if row_step == col_step => Self::DIAGONAL,
+            _ => Self::ANTI_DIAGONAL,
+        };
+        self.contains(direction)
+    }
+}
+
+impl Default for WinDirections {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for WinDirections {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl FromStr for WinDirections {
+    type Err = String;
+
+    /// Parses a comma separated list of `h`, `v` and `d` (both diagonals), e.g. `h,v`.
+    ///
+    /// The full names `horizontal`, `vertical` and `diagonal` are accepted too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|direction| match direction.trim().to_lowercase().as_str() {
+                "h" | "horizontal" => Ok(Self::HORIZONTAL),
+                "v" | "vertical" => Ok(Self::VERTICAL),
+                "d" | "diagonal" => Ok(Self::DIAGONAL | Self::ANTI_DIAGONAL),
+                other => Err(format!("unknown direction {:?}, expected h, v or d", other)),
+            })
+            .try_fold(Self(0), |directions, direction| Ok(directions | direction?))
+    }
+}
+
+/// Represents the run of tokens that won a game.
+#[derive(Clone, PartialEq, Debug)]
+pub struct WinningLine {
+    /// The player who owns the run.
+    pub player: Player,
+    /// The (row, column) of every token in the run, in order along the line.
+    ///
+    /// Runs found through the last move only reach `tokens_to_win - 1` cells past it, so a
+    /// longer overline is cut short there.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Represents how a game ended.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameResult {
+    /// The player won, with the (row, column) of every token in their winning line.
+    ///
+    /// The line is empty if the game was decided otherwise, e.g. by a forfeit or a tie-break.
+    Winner(Player, Vec<(usize, usize)>),
+    /// Nobody won.
+    Draw,
+    /// The game was quit before it was decided.
+    Aborted,
+}
+
+/// Represents the outcome of a game played to the end, for programs embedding the game.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameOutcome {
+    /// How the game ended.
+    pub result: GameResult,
+    /// Every move played as (player token, column), oldest first.
+    pub history: Vec<(String, usize)>,
+}
+
+/// Represents an invalid game configuration.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameError {
+    /// Fewer than two players were provided.
+    TooFewPlayers,
+    /// There are more players than tokens that can be assigned automatically.
+    TooManyPlayers,
+    /// Two or more players share the same token.
+    DuplicateToken(String),
+    /// A player's token is whitespace, which can't be told apart from an empty cell.
+    InvalidToken(String),
+    /// Two or more players chose the same color.
+    DuplicateColor(Color),
+    /// The board does not have enough cells for every player to make a line.
+    BoardTooSmall,
+    /// The board has zero rows or columns.
+    InvalidDimensions,
+    /// The number of tokens to win is less than 2.
+    TokensToWinTooSmall,
+    /// The number of tokens to win does not fit on the board.
+    TokensToWinTooLarge,
+    /// A move was attempted after the game ended.
+    GameOver,
+    /// A move was rejected by the board.
+    InvalidMove(BoardError),
+    /// A move was rejected by the move filter, with the message it shows.
+    MoveRejected(String),
+    /// A saved game could not be read or describes an unplayable state.
+    InvalidSave(String),
+    /// There is no move to undo.
+    NothingToUndo,
+    /// There is no undone move to redo.
+    NothingToRedo,
+    /// A replayed move was made by a player whose turn it wasn't.
+    OutOfTurn(String),
+    /// There is no player at the index.
+    NoSuchPlayer(usize),
+    /// The setting can only be changed before the first move.
+    AlreadyStarted,
+    /// A token was popped out while the Pop Out variant is disabled.
+    PopOutDisabled,
+    /// A token was put in a cell while tokens must be dropped.
+    FreePlacementDisabled,
+    /// The tokens placed as a handicap already decide the game.
+    HandicapEndsGame,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::TooFewPlayers => write!(f, "Must have at least 2 players."),
+            GameError::TooManyPlayers => write!(f, "Not enough tokens for every player."),
+            GameError::DuplicateToken(token) => write!(f, "Duplicate token found: {}", token),
+            GameError::InvalidToken(token) => write!(f, "Invalid token: {:?}", token),
+            GameError::DuplicateColor(color) => write!(f, "Duplicate color found: {:?}", color),
+            GameError::BoardTooSmall => write!(f, "Too many players for the board size."),
+            GameError::InvalidDimensions => write!(f, "Rows and columns must be greater than 0."),
+            GameError::TokensToWinTooSmall => write!(f, "Tokens to win must be at least 2."),
+            GameError::TokensToWinTooLarge => {
+                write!(
+                    f,
+                    "Tokens to win cannot be greater than both rows and columns."
+                )
+            }
+            GameError::GameOver => write!(f, "The game is already over."),
+            GameError::InvalidMove(err) => write!(f, "Invalid move. {}", err),
+            GameError::MoveRejected(message) => write!(f, "Invalid move. {}", message),
+            GameError::InvalidSave(reason) => write!(f, "Invalid saved game: {}", reason),
+            GameError::NothingToUndo => write!(f, "There is no move to undo."),
+            GameError::NothingToRedo => write!(f, "There is no move to redo."),
+            GameError::OutOfTurn(token) => write!(f, "Move by {} was made out of turn.", token),
+            GameError::NoSuchPlayer(index) => write!(f, "There is no player {}.", index),
+            GameError::AlreadyStarted => write!(f, "The game has already started."),
+            GameError::PopOutDisabled => write!(f, "Popping out tokens is not allowed."),
+            GameError::FreePlacementDisabled => {
+                write!(f, "Tokens must be dropped into a column.")
+            }
+            GameError::HandicapEndsGame => {
+                write!(f, "The handicap tokens already decide the game.")
+            }
+        }
+    }
+}
+
+impl GameError {
+    /// Describes the error to the players, with lanes and cells labelled by `labels`.
+    ///
+    /// Only rejected moves mention lanes or cells; every other error is described as by its
+    /// `Display` implementation.
+    pub fn describe(&self, labels: &Labels) -> String {
+        match self {
+            GameError::InvalidMove(err) => format!("Invalid move. {}", err.describe(labels)),
+            err => err.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<BoardError> for GameError {
+    fn from(err: BoardError) -> Self {
+        GameError::InvalidMove(err)
+    }
+}
+
+/// Describes how to set up a game.
+#[derive(Clone, Debug)]
+pub struct GameConfig {
+    /// The number of rows on the board.
+    pub rows: usize,
+    /// The number of columns on the board.
+    pub cols: usize,
+    /// The number of tokens in a row required to win the game.
+    pub tokens_to_win: usize,
+    /// The list of players in the game.
+    pub players: Vec<Player>,
+}
+
+/// Decides whether a player may drop a token into a column, on top of the rules of the game.
+///
+/// It is given the board, the column, the player to move and every move played so far as
+/// (player token, column), oldest first.
+pub type MoveFilter = Box<dyn Fn(&Board, usize, &Player, &[(String, usize)]) -> bool>;
+
+/// Represents the state of a game that its status depends on, so the status can also be found
+/// for a move that was not played.
+#[derive(Clone, Copy)]
+struct Position<'a> {
+    /// The game board.
+    board: &'a Board,
+    /// The (row, column) of the most recently placed token.
+    last_move: Option<(usize, usize)>,
+    /// The index of the player to move.
+    turn: usize,
+    /// The number of moves played.
+    moves_played: usize,
+}
+
 /// Represents a game of Connect Four.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     /// The game board state.
     pub board: Board,
@@ -31,210 +359,2612 @@ pub struct Game {
     pub tokens_to_win: usize,
     /// The list of players in the game.
     pub players: Vec<Player>,
+    /// Whether players may pop their own tokens out of the bottom row (Pop Out variant).
+    pub pop_out: bool,
+    /// Where players may put their tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub placement: Placement,
+    /// Whether only lines of exactly `tokens_to_win` tokens win. Longer lines are dead.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exact_length: bool,
+    /// The directions lines may win in.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub win_directions: WinDirections,
+    /// The most moves that may be played. The game is a draw once they are used up without a
+    /// winner, however many cells are still empty.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_moves: Option<usize>,
+    /// How the game is decided when the board fills up without a winner.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tiebreak: TieBreak,
+    /// What happens when a player runs out of time on their turn.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timeout_action: TimeoutAction,
+    /// How long to pause between moves when every player is a computer.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub move_delay: Duration,
+    /// How long a dropped token takes to fall past each cell, if drops are animated.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub drop_delay: Option<Duration>,
+    /// Whether to warn above the board about moves that would win on the spot.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub show_threats: bool,
+    /// Whether to comment on the game, e.g. when a player sets up a double threat.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub commentary: bool,
+    /// The round of the match this game is played in, if it is part of one.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub round: Option<usize>,
+    /// How long a player may think about a move before a warning is shown, if at all.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub slow_move: Option<Duration>,
+    /// How the board and lanes are shown to the players.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub display: DisplaySettings,
+    /// Whether the center is banned on the first move, see `set_ban_first_center`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    ban_first_center: bool,
     /// The index of the current player's turn.
     current_turn: usize,
+    /// The indices of the players who were eliminated, e.g. by forfeiting, and no longer take
+    /// turns.
+    #[cfg_attr(feature = "serde", serde(default))]
+    eliminated: BTreeSet<usize>,
+    /// The (row, column) of the most recently placed token.
+    last_move: Option<(usize, usize)>,
+    /// Every move played so far as (player token, column), oldest first.
+    history: Vec<(String, usize)>,
+    /// Undone moves that can be played again, most recently undone last.
+    redo_stack: Vec<(String, usize)>,
+    /// The seed used for random decisions, if any were made.
+    seed: Option<u64>,
+    /// The strategy choosing the moves of each player.
+    ///
+    /// Strategies are not saved; every player of a loaded game is human.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    strategies: Vec<Box<dyn Strategy>>,
+    /// Receives every event of the game.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observer: Option<Observer>,
+    /// The custom rule moves must pass, and the message shown when it rejects one.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    move_filter: Option<(MoveFilter, String)>,
+    /// Supplies the lines typed by human players.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    input: Box<dyn InputSource>,
+    /// Shows the game to the players.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output: Box<dyn Output>,
+    /// Statistics about the moves made so far.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    stats: GameStats,
+    /// The lines of the board, for the checks that scan all of them.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    line_cache: LineCache,
 }
 
 impl Game {
     /// Creates a new game of Connect Four.
     ///
-    /// ## Panics
+    /// Players without a token are assigned one from their position: `a` to `z`, then `A` to
+    /// `Z`, then `0` to `9`, skipping tokens chosen by other players. Every player is assigned a
+    /// color from their position.
     ///
-    /// - Panics if the number of players is less than 2.
+    /// ## Errors
+    ///
+    /// - If the number of players is less than 2.
     /// - If there are duplicate tokens among players.
-    /// - If the game configuration is invalid:
-    /// - If the number of players is too many for the board size
+    /// - If the game configuration is invalid.
+    /// - If the number of players is too many for the board size.
     pub fn new(
         row_count: usize,
         col_count: usize,
         tokens_to_win: usize,
-        players: Vec<Player>,
-    ) -> Self {
+        mut players: Vec<Player>,
+    ) -> Result<Self, GameError> {
         if players.len() < 2 {
-            panic!("Must have at least 2 players.")
+            return Err(GameError::TooFewPlayers);
         }
 
-        Self::validate_players(&players);
-
-        if let Err(err) = Self::validate_game_config(row_count, col_count, tokens_to_win) {
-            panic!("Invalid game configuration: {}", err);
-        }
+        Self::validate_colors(&players)?;
+        Self::assign_tokens(&mut players)?;
+        Self::validate_players(&players)?;
+        Self::validate_game_config(row_count, col_count, tokens_to_win)?;
 
         if players.len() * tokens_to_win > row_count * col_count {
-            panic!("Too many players for the board size.");
+            return Err(GameError::BoardTooSmall);
         }
 
-        Self {
+        // Every player is human until told otherwise
+        let strategies = Self::human_strategies(players.len());
+        let stats = GameStats::new(players.len());
+
+        Ok(Self {
             board: Board::new(row_count, col_count),
             tokens_to_win,
             players,
+            pop_out: false,
+            placement: Placement::Gravity,
+            exact_length: false,
+            win_directions: WinDirections::ALL,
+            max_moves: None,
+            tiebreak: TieBreak::Draw,
+            timeout_action: TimeoutAction::Skip,
+            move_delay: Duration::ZERO,
+            drop_delay: None,
+            show_threats: false,
+            commentary: false,
+            round: None,
+            slow_move: None,
+            display: DisplaySettings::default(),
+            ban_first_center: false,
             current_turn: 0,
+            eliminated: BTreeSet::new(),
+            last_move: None,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            seed: None,
+            strategies,
+            observer: None,
+            move_filter: None,
+            input: Box::new(StdinInput::default()),
+            output: Box::new(StdoutOutput::default()),
+            stats,
+            line_cache: LineCache::default(),
+        })
+    }
+
+    /// Serializes the game to JSON so it can be resumed later.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("game state is always serializable")
+    }
+
+    /// Loads a game previously saved with `to_json`.
+    ///
+    /// Every player of the loaded game is controlled by a `HumanStrategy`.
+    ///
+    /// ## Errors
+    ///
+    /// - If the JSON cannot be parsed.
+    /// - If the saved configuration or board is inconsistent with the players.
+    #[cfg(feature = "serde")]
+    pub fn from_json(s: &str) -> Result<Self, GameError> {
+        let mut game: Self =
+            serde_json::from_str(s).map_err(|err| GameError::InvalidSave(err.to_string()))?;
+
+        let rows = game.board.rows.len();
+        let cols = game.board.rows.first().map_or(0, |row| row.len());
+
+        if game.players.len() < 2 {
+            return Err(GameError::TooFewPlayers);
+        }
+        Self::validate_players(&game.players)?;
+        Self::validate_game_config(rows, cols, game.tokens_to_win)?;
+        if game.players.len() * game.tokens_to_win > rows * cols {
+            return Err(GameError::BoardTooSmall);
+        }
+
+        if game.board.rows.iter().any(|row| row.len() != cols) {
+            return Err(GameError::InvalidSave(
+                "board rows differ in length".to_string(),
+            ));
+        }
+        if game.current_turn >= game.players.len() {
+            return Err(GameError::InvalidSave(
+                "current turn is out of range".to_string(),
+            ));
+        }
+        if game
+            .eliminated
+            .iter()
+            .any(|&index| index >= game.players.len() || index == game.current_turn)
+        {
+            return Err(GameError::InvalidSave(
+                "eliminated players are out of range or still to move".to_string(),
+            ));
         }
+        if game
+            .board
+            .rows
+            .iter()
+            .flatten()
+            .flatten()
+            .any(|player| !game.players.contains(player))
+        {
+            return Err(GameError::InvalidSave(
+                "board contains an unknown player".to_string(),
+            ));
+        }
+        if game
+            .history
+            .iter()
+            .chain(&game.redo_stack)
+            .any(|(token, _)| !game.players.iter().any(|player| player.token == *token))
+        {
+            return Err(GameError::InvalidSave(
+                "history contains an unknown player".to_string(),
+            ));
+        }
+
+        game.strategies = Self::human_strategies(game.players.len());
+        // The saved last move can't be trusted, so scan the whole board for a winner
+        game.last_move = None;
+
+        Ok(game)
     }
 
-    /// Advances the game to the next turn.
-    fn next_turn(&mut self) {
-        // Increment current turn and loop back to 0 if at the end
-        self.current_turn = (self.current_turn + 1) % self.players.len();
+    /// Registers a callback that receives every event of the game, replacing any previous one.
+    pub fn set_observer(&mut self, observer: Observer) {
+        self.observer = Some(observer);
     }
 
-    /// Validates that there are no duplicate tokens among players.
-    fn validate_players(players: &[Player]) {
-        let mut seen_tokens = HashSet::new();
+    /// Adds a custom rule that every dropped token must pass, replacing any previous one.
+    ///
+    /// Moves the filter rejects fail with `message`. Computer players are moved to the first
+    /// column the filter allows. If it allows none of the open columns, it is ignored until it
+    /// does, so the game can't get stuck.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::{Game, GameError, Player};
+    /// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.set_move_filter(
+    ///     Box::new(|_board, col, me, history| {
+    ///         let last = history.iter().rev().find(|(token, _)| *token == me.token);
+    ///         last.map_or(true, |&(_, last)| last != col)
+    ///     }),
+    ///     "You can't play the same column twice in a row.",
+    /// );
+    ///
+    /// game.play_move(3).unwrap();
+    /// game.play_move(3).unwrap();
+    /// assert_eq!(
+    ///     game.play_move(3),
+    ///     Err(GameError::MoveRejected("You can't play the same column twice in a row.".to_string()))
+    /// );
+    /// assert!(game.play_move(2).is_ok());
+    /// ```
+    pub fn set_move_filter(&mut self, filter: MoveFilter, message: &str) {
+        self.move_filter = Some((filter, message.to_string()));
+    }
 
-        for player in players {
-            if !seen_tokens.insert(player.token) {
-                panic!("Duplicate token found for player: {}", player.name);
-            }
+    /// Sets whether the center column, or both center columns of an even board, may not be
+    /// played on the first move.
+    ///
+    /// The ban holds until a move has been played, and again once every move has been undone.
+    /// Handicap tokens are not moves, so they neither lift the ban nor are held back by it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::{Game, GameError, Player};
+    /// # use connect_four::game::board::BoardError;
+    /// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.set_ban_first_center(true);
+    ///
+    /// assert_eq!(game.play_move(3), Err(GameError::InvalidMove(BoardError::CenterBanned(3))));
+    /// game.play_move(2).unwrap();
+    /// assert!(game.play_move(3).is_ok());
+    /// ```
+    pub fn set_ban_first_center(&mut self, ban: bool) {
+        self.ban_first_center = ban;
+        self.update_center_ban();
+    }
+
+    /// Bans the center on the board while no move has been played, see `set_ban_first_center`.
+    fn update_center_ban(&mut self) {
+        self.board.ban_first_center = self.ban_first_center && self.moves_played() == 0;
+    }
+
+    /// Checks if the move filter lets the current player drop a token into `col`.
+    ///
+    /// Always true without a filter, or when the filter allows none of the open columns.
+    fn filter_allows(&self, col: usize) -> bool {
+        let Some((filter, _)) = &self.move_filter else {
+            return true;
+        };
+        let allows = |col| filter(&self.board, col, self.current_player(), &self.history);
+
+        allows(col) || !self.board.open_columns().into_iter().any(allows)
+    }
+
+    /// Checks that the move filter lets the current player drop a token into `col`.
+    ///
+    /// Columns a token can't be dropped into are left to the board to reject.
+    ///
+    /// ## Errors
+    ///
+    /// - If the move filter rejects the move.
+    fn check_filter(&self, col: usize) -> Result<(), GameError> {
+        if self.board.valid_move(col) && !self.filter_allows(col) {
+            let message = self
+                .move_filter
+                .as_ref()
+                .map(|(_, message)| message.clone());
+            return Err(GameError::MoveRejected(message.unwrap_or_default()));
         }
+
+        Ok(())
     }
 
-    /// Validates the game configuration.
+    /// Passes the event to the observer, if there is one.
+    fn emit(&mut self, event: GameEvent) {
+        if let Some(observer) = self.observer.as_mut() {
+            observer(&event);
+        }
+    }
+
+    /// Emits the event that follows from the status: the end of the game or the next turn.
+    fn emit_status(&mut self, status: &GameStatus) {
+        if status.is_over() {
+            self.stats.record_end();
+        }
+
+        let event = match status {
+            GameStatus::Ongoing => GameEvent::TurnStarted {
+                player: self.current_player().clone(),
+            },
+            GameStatus::Draw => GameEvent::Draw,
+            GameStatus::Win(player) => GameEvent::WinDetected {
+                player: player.clone(),
+            },
+        };
+        self.emit(event);
+    }
+
+    /// Returns a `HumanStrategy` for each of `count` players.
+    fn human_strategies(count: usize) -> Vec<Box<dyn Strategy>> {
+        (0..count)
+            .map(|_| Box::new(HumanStrategy::new()) as Box<dyn Strategy>)
+            .collect()
+    }
+
+    /// Sets the strategy choosing the moves of the player at `player_index`.
     ///
-    /// Returns an error message if the configuration is invalid.
+    /// Players use a `HumanStrategy` by default.
+    pub fn set_strategy(&mut self, player_index: usize, strategy: Box<dyn Strategy>) {
+        self.strategies[player_index] = strategy;
+    }
+
+    /// Sets where the lines typed by human players are read from.
     ///
-    /// # Errors
-    /// - If `rows` or `cols` is less than 1.
-    /// - If `tokens_to_win` is less than 2.
-    /// - If `tokens_to_win` is greater than `rows` or `cols`.
-    fn validate_game_config(rows: usize, cols: usize, tokens_to_win: usize) -> Result<(), String> {
-        if rows < 1 || cols < 1 {
-            return Err("Rows and columns must be greater than 0.".to_string());
+    /// Input is read from stdin by default.
+    pub fn set_input(&mut self, input: Box<dyn InputSource>) {
+        self.input = input;
+    }
+
+    /// Takes the input source out of the game, e.g. to keep reading from it once the game ended.
+    ///
+    /// The game reads from a new stdin input afterwards.
+    pub fn take_input(&mut self) -> Box<dyn InputSource> {
+        std::mem::take(&mut self.input)
+    }
+
+    /// Takes the output out of the game, e.g. to keep showing messages once the game ended.
+    ///
+    /// The game is printed to stdout afterwards.
+    pub fn take_output(&mut self) -> Box<dyn Output> {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Sets where the game is shown to the players.
+    ///
+    /// The game is printed to stdout by default.
+    pub fn set_output(&mut self, output: Box<dyn Output>) {
+        self.output = output;
+    }
+
+    /// Returns whether no player is interactive, so the game plays itself and is only watched.
+    pub fn is_spectated(&self) -> bool {
+        !self
+            .strategies
+            .iter()
+            .any(|strategy| strategy.is_interactive())
+    }
+
+    /// Returns the players that move after the current player, in turn order.
+    ///
+    /// Eliminated players are left out.
+    fn opponents(&self) -> Vec<Player> {
+        (1..self.players.len())
+            .map(|offset| (self.current_turn + offset) % self.players.len())
+            .filter(|index| !self.eliminated.contains(index))
+            .map(|index| self.players[index].clone())
+            .collect()
+    }
+
+    /// Sets which player makes the first move.
+    ///
+    /// ## Errors
+    ///
+    /// - If there is no player at `player_index`.
+    /// - If a move has already been played.
+    pub fn set_first_player(&mut self, player_index: usize) -> Result<(), GameError> {
+        if player_index >= self.players.len() {
+            return Err(GameError::NoSuchPlayer(player_index));
         }
-        if tokens_to_win < 2 {
-            return Err("Tokens to win must be at least 2.".to_string());
+        if !self.history.is_empty() {
+            return Err(GameError::AlreadyStarted);
         }
-        if tokens_to_win > rows || tokens_to_win > cols {
-            return Err("Tokens to win cannot be greater than rows or columns.".to_string());
+
+        self.current_turn = player_index;
+        Ok(())
+    }
+
+    /// Clears the board and the moves played so the same players can play again.
+    ///
+    /// The players, settings, strategies and observer are kept. Eliminated players rejoin, and
+    /// `first_player` moves first, so passing the next player rotates who starts. Handicap
+    /// tokens are cleared too and have to be applied again.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.play_move(3).unwrap();
+    /// game.play_move(4).unwrap();
+    ///
+    /// game.reset(1).unwrap();
+    /// assert_eq!(game.board.rows.len(), 6);
+    /// assert!(game.board.rows.iter().all(|row| row.len() == 7));
+    /// assert!(game.board.iter_cells().all(|(_, _, cell)| cell.is_none()));
+    /// assert!(game.history().is_empty());
+    /// assert_eq!(game.current_turn(), 1);
+    /// assert_eq!(game.status(), GameStatus::Ongoing);
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// - If there is no player at `first_player`.
+    pub fn reset(&mut self, first_player: usize) -> Result<(), GameError> {
+        if first_player >= self.players.len() {
+            return Err(GameError::NoSuchPlayer(first_player));
         }
 
+        self.board.reset();
+        self.current_turn = first_player;
+        self.eliminated.clear();
+        self.last_move = None;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.stats = GameStats::new(self.players.len());
+        self.update_center_ban();
+
         Ok(())
     }
 
-    /// Prompts the current player for a valid column input.
-    /// Returns the column number entered by the player.
-    fn get_valid_input(&self) -> usize {
-        loop {
-            let mut input_line = String::new();
-            println!("Please enter a column to play: ");
+    /// Places extra tokens before the game begins, to balance out a difference in skill.
+    ///
+    /// Each move is a (player index, column) pair and is dropped like a regular move, so the
+    /// board's gravity applies. The tokens are put on the board directly: they are not moves, so
+    /// they are not part of the history or the statistics, cannot be undone, don't count towards
+    /// `max_moves` and aren't checked by the move filter. The player who moves first is unchanged.
+    ///
+    /// ## Errors
+    ///
+    /// The game is left unchanged if any of the moves is rejected.
+    ///
+    /// - If a move has already been played.
+    /// - If there is no player at an index, or a column is out of range or full.
+    /// - If the handicap tokens already win or draw the game.
+    pub fn apply_handicap(&mut self, moves: &[(usize, usize)]) -> Result<(), GameError> {
+        if !self.history.is_empty() {
+            return Err(GameError::AlreadyStarted);
+        }
+        if let Some(&(player_index, _)) = moves
+            .iter()
+            .find(|(player_index, _)| *player_index >= self.players.len())
+        {
+            return Err(GameError::NoSuchPlayer(player_index));
+        }
 
-            if io::stdin().read_line(&mut input_line).is_err() {
-                println!("Failed to read input. Please try again.");
-                continue;
-            }
+        // Drop the tokens on a copy, so a rejected handicap leaves the board as it was. They
+        // aren't the first move, so they may go in the center.
+        let mut board = self.board.clone();
+        board.ban_first_center = false;
+        for &(player_index, col) in moves {
+            board.place_token(col, self.players[player_index].clone())?;
+        }
+        board.last_placed = None;
+        board.ban_first_center = self.board.ban_first_center;
 
-            match input_line.trim().parse::<usize>() {
-                Ok(value) if self.board.valid_move(value) => return value,
-                Ok(_) => println!("Invalid move. Column is either full or out of range."),
-                Err(_) => println!("Invalid input. Please enter a valid integer."),
-            }
+        let position = Position {
+            board: &board,
+            last_move: None,
+            turn: self.current_turn,
+            moves_played: 0,
+        };
+        if self.status_at(position).is_over() {
+            return Err(GameError::HandicapEndsGame);
         }
+
+        self.board = board;
+        Ok(())
     }
 
-    /// Checks a line for a winner.
-    fn check_line(line: &[BoardCell], tokens_to_win: usize) -> BoardCell {
-        let mut count = 0;
-        let mut last_player: BoardCell = None;
-
-        for cell in line {
-            if let Some(player) = cell {
-                if Some(player) == last_player.as_ref() {
-                    count += 1;
-                    if count == tokens_to_win {
-                        return Some(player.clone());
-                    }
-                } else {
-                    count = 1;
-                    last_player = Some(player.clone());
-                }
-            } else {
-                count = 0;
-                last_player = None;
-            }
-        }
+    /// Picks the first player at random using `seed`.
+    ///
+    /// The same seed always picks the same player. The seed is stored on the game.
+    ///
+    /// ## Errors
+    ///
+    /// - If a move has already been played.
+    pub fn randomize_first_player(&mut self, seed: u64) -> Result<(), GameError> {
+        let first = Rng::new(seed).next_below(self.players.len());
+        self.set_first_player(first)?;
+        self.seed = Some(seed);
+        Ok(())
+    }
 
-        None
+    /// Returns the seed used for random decisions, if any were made.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
     }
 
-    /// Finds the winner of the game.
-    /// Returns the winning player if there is a winner, otherwise returns None.
-    fn find_winner(&self) -> BoardCell {
-        // Check rows for winner
-        for row in &self.board.rows {
-            if let Some(winner) = Self::check_line(row, self.tokens_to_win) {
-                return Some(winner);
+    /// Sets the seed for random decisions, so the game can be reproduced from it.
+    ///
+    /// Random bots and random moves after a timeout derive their choices from this seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Returns a seed for random decisions of the player at `player_index`.
+    ///
+    /// Each player gets their own sequence. Without a game seed one is picked from the clock
+    /// and stored on the game.
+    pub fn player_seed(&mut self, player_index: usize) -> u64 {
+        let seed = *self.seed.get_or_insert_with(Rng::time_seed);
+        seed.wrapping_add(player_index as u64)
+    }
+
+    /// Describes the winner of the game, e.g. "The winner is: Alice (a)".
+    ///
+    /// Team wins name every member of the team, since lines may mix their tokens.
+    pub fn describe_winner(&self, winner: &Player) -> String {
+        match winner.team {
+            Some(team) => {
+                let members: Vec<&str> = self
+                    .players
+                    .iter()
+                    .filter(|player| player.is_teammate(winner))
+                    .map(|player| player.name.as_str())
+                    .collect();
+                format!("The winner is: team {} ({})", team, members.join(", "))
             }
+            None => format!("The winner is: {}", winner),
         }
+    }
+
+    /// Returns the statistics of the game so far.
+    pub fn stats(&self) -> &GameStats {
+        &self.stats
+    }
 
-        // Check columns for winner
-        for col in 0..self.board.rows[0].len() {
-            let column: Vec<_> = self.board.rows.iter().map(|row| row[col].clone()).collect();
-            if let Some(winner) = Self::check_line(&column, self.tokens_to_win) {
-                return Some(winner);
+    /// Summarizes the game: the number of moves made, the winning line if any, and how long
+    /// the game took.
+    pub fn summary(&self) -> String {
+        let per_player: Vec<String> = self
+            .players
+            .iter()
+            .zip(self.stats.moves_per_player())
+            .map(|(player, moves)| format!("{} {}", player.name, moves))
+            .collect();
+        let mut output = format!(
+            "Moves: {} ({})\n",
+            self.stats.total_moves(),
+            per_player.join(", ")
+        );
+
+        if let Some(line) = self.find_winner() {
+            let cell = |&(row, col): &(usize, usize)| {
+                format!(
+                    "column {}, row {}",
+                    self.display.labels.lane(col),
+                    self.display.labels.row(row)
+                )
+            };
+            if let (Some(first), Some(last)) = (line.cells.first(), line.cells.last()) {
+                output.push_str(&format!(
+                    "Winning line: {} to {}\n",
+                    cell(first),
+                    cell(last)
+                ));
             }
         }
 
-        // Check top-left to bottom-right diagonals for winner
-        for diagonal in self.board.get_diagonals_top_left_to_bottom_right() {
-            if let Some(winner) = Self::check_line(&diagonal, self.tokens_to_win) {
-                return Some(winner);
-            }
+        let think_times: Vec<String> = self
+            .players
+            .iter()
+            .zip(self.stats.think_times())
+            .filter(|(_, think_time)| think_time.moves > 0)
+            .map(|(player, think_time)| {
+                format!(
+                    "{} avg {:.2}s, max {:.2}s",
+                    player.name,
+                    think_time.average().as_secs_f64(),
+                    think_time.max.as_secs_f64()
+                )
+            })
+            .collect();
+        if !think_times.is_empty() {
+            output.push_str(&format!("Think time: {}\n", think_times.join("; ")));
         }
 
-        // Check top-right to bottom-left diagonals for winner
-        for diagonal in self.board.get_diagonals_top_right_to_bottom_left() {
-            if let Some(winner) = Self::check_line(&diagonal, self.tokens_to_win) {
-                return Some(winner);
+        let seconds = self.stats.duration().as_secs();
+        output.push_str(&format!("Duration: {}m {:02}s", seconds / 60, seconds % 60));
+        output
+    }
+
+    /// Returns the player whose turn it is.
+    pub fn current_player(&self) -> &Player {
+        &self.players[self.current_turn]
+    }
+
+    /// Returns the index of the player whose turn it is.
+    pub fn current_turn(&self) -> usize {
+        self.current_turn
+    }
+
+    /// Advances the game to the next turn, skipping eliminated players.
+    fn next_turn(&mut self) {
+        self.current_turn = self.turn_after(self.current_turn);
+    }
+
+    /// Returns the index of the player who moves after the player at `turn`, skipping
+    /// eliminated players.
+    fn turn_after(&self, turn: usize) -> usize {
+        let mut next = turn;
+        for _ in 0..self.players.len() {
+            // Increment the turn and loop back to 0 if at the end
+            next = (next + 1) % self.players.len();
+            if !self.eliminated.contains(&next) {
+                break;
             }
         }
+        next
+    }
 
-        // No winner found
-        None
+    /// Returns the players who have not been eliminated, in turn order.
+    fn active_players(&self) -> Vec<Player> {
+        self.players
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.eliminated.contains(index))
+            .map(|(_, player)| player.clone())
+            .collect()
     }
 
-    /// Determines the status of the game.
-    fn status(&self) -> GameStatus {
-        if self.board.is_board_full() {
-            return GameStatus::Draw;
+    /// Returns whether the player at `index` was eliminated and no longer takes turns.
+    pub fn is_eliminated(&self, index: usize) -> bool {
+        self.eliminated.contains(&index)
+    }
+
+    /// Removes the player at `index` from the turn rotation, e.g. when they leave the game.
+    ///
+    /// The player's tokens stay on the board. If it is their turn, the turn passes on to the
+    /// next remaining player; otherwise the turn stays where it is. Once every remaining player
+    /// is on the same side, that side wins. Eliminations cannot be undone, so the undo and redo
+    /// history is cleared. Eliminating a player twice has no further effect.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let players = ["A", "B", "C", "D"].map(Player::new).to_vec();
+    /// let mut game = Game::new(6, 7, 4, players).unwrap();
+    ///
+    /// // A player in the middle is skipped
+    /// game.eliminate(2).unwrap();
+    /// assert_eq!(game.current_turn(), 0);
+    /// game.play_move(0).unwrap();
+    /// game.play_move(1).unwrap();
+    /// assert_eq!(game.current_turn(), 3);
+    ///
+    /// // Eliminating the current and last player wraps around to the first
+    /// game.eliminate(3).unwrap();
+    /// assert_eq!(game.current_turn(), 0);
+    ///
+    /// // The last player standing wins
+    /// let status = game.eliminate(0).unwrap();
+    /// assert!(matches!(status, GameStatus::Win(player) if player.name == "B"));
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// - If there is no player at `index`.
+    /// - If the game is already over.
+    pub fn eliminate(&mut self, index: usize) -> Result<GameStatus, GameError> {
+        if index >= self.players.len() {
+            return Err(GameError::NoSuchPlayer(index));
+        }
+        if self.status().is_over() {
+            return Err(GameError::GameOver);
+        }
+        if !self.eliminated.insert(index) {
+            return Ok(self.status());
         }
 
-        match self.find_winner() {
-            Some(winner) => GameStatus::Win(winner),
-            None => GameStatus::Ongoing,
+        self.history.clear();
+        self.redo_stack.clear();
+        self.emit(GameEvent::PlayerEliminated {
+            player: self.players[index].clone(),
+        });
+
+        if index == self.current_turn {
+            self.next_turn();
         }
+
+        let status = self.status();
+        self.emit_status(&status);
+        Ok(status)
     }
 
-    /// Starts the game loop.
-    /// The game will continue until a player wins or the game ends in a draw.
-    pub fn start(&mut self) {
-        loop {
-            util::clear_terminal();
+    /// Makes the current player give up and passes the turn on.
+    ///
+    /// See `eliminate` for what happens to the player.
+    ///
+    /// ## Errors
+    ///
+    /// - If the game is already over.
+    pub fn forfeit(&mut self) -> Result<GameStatus, GameError> {
+        self.eliminate(self.current_turn)
+    }
 
-            println!("{}", self.board.display());
+    /// Assigns a token and a color to every player without one.
+    ///
+    /// Colors are taken from the palette in order, skipping colors chosen by other players.
+    fn assign_tokens(players: &mut [Player]) -> Result<(), GameError> {
+        let chosen: HashSet<String> = players.iter().map(|player| player.token.clone()).collect();
+        let mut available = AUTO_TOKENS
+            .chars()
+            .map(String::from)
+            .filter(|token| !chosen.contains(token));
 
-            match self.status() {
-                GameStatus::Ongoing => (),
-                GameStatus::Draw => {
-                    println!("Draw!");
-                    break;
-                }
-                GameStatus::Win(player) => {
-                    println!("The winner is: {} ({})", player.name, player.token);
-                    break;
-                }
+        let chosen_colors: Vec<Color> = players.iter().filter_map(|player| player.color).collect();
+        let mut palette: Vec<Color> = PALETTE
+            .into_iter()
+            .filter(|color| !chosen_colors.contains(color))
+            .collect();
+        // Every color is taken, so share them again
+        if palette.is_empty() {
+            palette = PALETTE.to_vec();
+        }
+        let mut colors = palette.into_iter().cycle();
+
+        for player in players.iter_mut() {
+            if player.token == AUTO_TOKEN {
+                player.token = available.next().ok_or(GameError::TooManyPlayers)?;
+            }
+            if player.color.is_none() {
+                player.color = colors.next();
             }
+        }
 
-            println!(
-                "{}'s ({}) Turn",
-                self.players[self.current_turn].name, self.players[self.current_turn].token
-            );
+        Ok(())
+    }
 
-            let input_col = self.get_valid_input();
-            self.board
-                .place_token(input_col, self.players[self.current_turn].clone());
+    /// Validates that no two players chose the same color.
+    fn validate_colors(players: &[Player]) -> Result<(), GameError> {
+        let mut seen_colors = Vec::new();
 
-            // chance turn
-            self.next_turn();
+        for color in players.iter().filter_map(|player| player.color) {
+            if seen_colors.contains(&color) {
+                return Err(GameError::DuplicateColor(color));
+            }
+            seen_colors.push(color);
         }
+
+        Ok(())
+    }
+
+    /// Validates that there are no duplicate, blank or overly wide tokens among players.
+    pub(crate) fn validate_players(players: &[Player]) -> Result<(), GameError> {
+        let mut seen_tokens = HashSet::new();
+
+        for player in players {
+            if player
+                .token
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control())
+                || player.token_width() > MAX_TOKEN_WIDTH
+            {
+                return Err(GameError::InvalidToken(player.token.clone()));
+            }
+            if !seen_tokens.insert(&player.token) {
+                return Err(GameError::DuplicateToken(player.token.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the game configuration.
+    ///
+    /// # Errors
+    /// - If `rows` or `cols` is less than 1.
+    /// - If `tokens_to_win` is less than 2.
+    /// - If `tokens_to_win` is greater than both `rows` and `cols`.
+    ///
+    /// A line only needs to fit in one direction: on a wide, short board only horizontal lines
+    /// can win, and the other directions simply never produce one.
+    fn validate_game_config(
+        rows: usize,
+        cols: usize,
+        tokens_to_win: usize,
+    ) -> Result<(), GameError> {
+        if rows < 1 || cols < 1 {
+            return Err(GameError::InvalidDimensions);
+        }
+        if tokens_to_win < 2 {
+            return Err(GameError::TokensToWinTooSmall);
+        }
+        if tokens_to_win > rows.max(cols) {
+            return Err(GameError::TokensToWinTooLarge);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a line for a winner.
+    ///
+    /// Returns the player with the first run of at least `tokens_to_win` tokens, along with the
+    /// index the run starts at and its full length, which may exceed `tokens_to_win`.
+    ///
+    /// With `exact_length` only runs of exactly `tokens_to_win` tokens win. Longer runs, known
+    /// as overlines, are skipped as a whole, since the cells next to a run never extend it.
+    ///
+    /// A run may mix the tokens of teammates, in which case the first player of the run is
+    /// returned.
+    pub(crate) fn check_line(
+        line: &[BoardCell],
+        tokens_to_win: usize,
+        exact_length: bool,
+    ) -> Option<(Player, usize, usize)> {
+        let mut start = 0;
+
+        while start < line.len() {
+            let Some(player) = &line[start] else {
+                start += 1;
+                continue;
+            };
+
+            let len = line[start..]
+                .iter()
+                .take_while(|cell| cell.as_ref().is_some_and(|other| other.is_teammate(player)))
+                .count();
+            if len == tokens_to_win || (len > tokens_to_win && !exact_length) {
+                return Some((player.clone(), start, len));
+            }
+
+            start += len;
+        }
+
+        None
+    }
+
+    /// Checks the cells at `positions` for a winning run, translating it to board coordinates.
+    ///
+    /// Runs on a ring may cross from its last cell to its first. A ring filled by a single side
+    /// counts as one run as long as the ring, however often it is followed around.
+    fn check_positions(
+        board: &Board,
+        positions: &[(usize, usize)],
+        tokens_to_win: usize,
+        exact_length: bool,
+    ) -> Option<WinningLine> {
+        if board.is_ring(positions) {
+            let cells: Vec<BoardCell> = positions
+                .iter()
+                .map(|&(row, col)| board.get(row, col).cloned().flatten())
+                .collect();
+            let len = cells.len();
+            let joined = |index: usize| match (&cells[(index + len - 1) % len], &cells[index]) {
+                (Some(before), Some(cell)) => before.is_teammate(cell),
+                _ => false,
+            };
+
+            // Start reading the ring where a run begins, so no run is split in two
+            match (0..len).find(|&index| !joined(index)) {
+                Some(start) => {
+                    let rotated: Vec<(usize, usize)> = (0..len)
+                        .map(|offset| positions[(start + offset) % len])
+                        .collect();
+                    return Self::check_positions_in_line(
+                        board,
+                        &rotated,
+                        tokens_to_win,
+                        exact_length,
+                    );
+                }
+                None => {
+                    let wins = len == tokens_to_win || (len > tokens_to_win && !exact_length);
+                    return cells[0].clone().filter(|_| wins).map(|player| WinningLine {
+                        player,
+                        cells: positions.to_vec(),
+                    });
+                }
+            }
+        }
+
+        Self::check_positions_in_line(board, positions, tokens_to_win, exact_length)
+    }
+
+    /// Checks the cells at `positions`, read from first to last, for a winning run.
+    fn check_positions_in_line(
+        board: &Board,
+        positions: &[(usize, usize)],
+        tokens_to_win: usize,
+        exact_length: bool,
+    ) -> Option<WinningLine> {
+        let line: Vec<BoardCell> = positions
+            .iter()
+            .map(|&(row, col)| board.get(row, col).cloned().flatten())
+            .collect();
+
+        Self::check_line(&line, tokens_to_win, exact_length).map(|(player, start, len)| {
+            WinningLine {
+                player,
+                cells: positions[start..start + len].to_vec(),
+            }
+        })
+    }
+
+    /// Finds the winner of the game.
+    /// Returns the winning line if there is a winner, otherwise returns None.
+    ///
+    /// Only the lines through the last placed token are checked, since any earlier win would
+    /// have ended the game. Without a last move (e.g. after a pop out or loading a game) the
+    /// whole board is scanned.
+    fn find_winner(&self) -> Option<WinningLine> {
+        self.winner_at(self.position())
+    }
+
+    /// Finds the winner in `position`, see `find_winner`.
+    fn winner_at(&self, position: Position) -> Option<WinningLine> {
+        match position.last_move {
+            Some((row, col)) => Self::find_winner_at(
+                position.board,
+                row,
+                col,
+                self.tokens_to_win,
+                self.exact_length,
+                self.win_directions,
+            ),
+            None => Self::find_winner_in(
+                position.board,
+                &self.line_cache.lines(position.board, self.tokens_to_win),
+                self.tokens_to_win,
+                self.exact_length,
+                self.win_directions,
+            ),
+        }
+    }
+
+    /// Returns the current position of the game.
+    fn position(&self) -> Position<'_> {
+        Position {
+            board: &self.board,
+            last_move: self.last_move,
+            turn: self.current_turn,
+            moves_played: self.moves_played(),
+        }
+    }
+
+    /// Finds a winning line passing through the cell at (row, col).
+    ///
+    /// Walks the horizontal, vertical and both diagonal lines outwards from the cell while they
+    /// hold tokens of its side, reaching at most `tokens_to_win - 1` cells in each direction, so
+    /// only a handful of cells are read however large the board is. With `exact_length` one more
+    /// cell is read each way, to tell a run of exactly `tokens_to_win` tokens from an overline.
+    /// Only lines in `directions` are checked.
+    ///
+    /// On a board that wraps around, lines continue across the left and right edges, without
+    /// visiting a cell twice.
+    pub(crate) fn find_winner_at(
+        board: &Board,
+        row: usize,
+        col: usize,
+        tokens_to_win: usize,
+        exact_length: bool,
+        directions: WinDirections,
+    ) -> Option<WinningLine> {
+        let player = board.get(row, col)?.as_ref()?;
+        let rows = board.rows.len() as isize;
+        let cols = board.rows[0].len() as isize;
+        let reach = tokens_to_win - 1 + usize::from(exact_length);
+
+        for (row_step, col_step) in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+            if !directions.allows(row_step, col_step) {
+                continue;
+            }
+
+            let mut cells = VecDeque::from([(row, col)]);
+            for sign in [-1, 1] {
+                let (mut r, mut c) = (row as isize, col as isize);
+
+                for _ in 0..reach {
+                    r += sign * row_step;
+                    c += sign * col_step;
+                    if board.wrap {
+                        c = c.rem_euclid(cols);
+                    }
+                    if r < 0 || r >= rows || c < 0 || c >= cols {
+                        break;
+                    }
+
+                    let cell = (r as usize, c as usize);
+                    let ours = board.rows[cell.0][cell.1]
+                        .as_ref()
+                        .is_some_and(|other| other.is_teammate(player));
+                    // A run around a whole ring ends where it started
+                    if !ours || cells.contains(&cell) {
+                        break;
+                    }
+
+                    if sign < 0 {
+                        cells.push_front(cell);
+                    } else {
+                        cells.push_back(cell);
+                    }
+                }
+            }
+
+            let len = cells.len();
+            if len == tokens_to_win || (len > tokens_to_win && !exact_length) {
+                let (first_row, first_col) = cells[0];
+                return Some(WinningLine {
+                    player: board.rows[first_row][first_col].clone()?,
+                    cells: cells.into(),
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Finds the winner on an arbitrary board by scanning every row, column and diagonal.
+    ///
+    /// Only lines in `directions` are checked.
+    pub(crate) fn find_winner_on(
+        board: &Board,
+        tokens_to_win: usize,
+        exact_length: bool,
+        directions: WinDirections,
+    ) -> Option<WinningLine> {
+        Self::find_winner_in(
+            board,
+            &board.all_lines(tokens_to_win),
+            tokens_to_win,
+            exact_length,
+            directions,
+        )
+    }
+
+    /// Finds the winner on an arbitrary board by scanning `lines`, which are the lines returned
+    /// by `Board::all_lines`.
+    fn find_winner_in(
+        board: &Board,
+        lines: &[Vec<(usize, usize)>],
+        tokens_to_win: usize,
+        exact_length: bool,
+        directions: WinDirections,
+    ) -> Option<WinningLine> {
+        lines
+            .iter()
+            .filter(|positions| Self::runs_in(board, positions, directions))
+            .find_map(|positions| {
+                Self::check_positions(board, positions, tokens_to_win, exact_length)
+            })
+    }
+
+    /// Returns whether the line of the board at `positions` runs in one of `directions`.
+    fn runs_in(board: &Board, positions: &[(usize, usize)], directions: WinDirections) -> bool {
+        match positions {
+            [(row, col), (next_row, next_col), ..] => {
+                let mut col_step = *next_col as isize - *col as isize;
+                // A line that wraps around may cross the edge between its first cells
+                if board.wrap && col_step.abs() > 1 {
+                    col_step -= col_step.signum() * board.rows[0].len() as isize;
+                }
+                directions.allows(*next_row as isize - *row as isize, col_step)
+            }
+            // A single cell never holds a line of two or more tokens
+            _ => false,
+        }
+    }
+
+    /// Returns the status of the game.
+    ///
+    /// A winner is checked first so that a move filling the last empty cell
+    /// while completing a line is reported as a win rather than a draw.
+    ///
+    /// Once players have been eliminated and every remaining player is on the same side, the first
+    /// of them is reported as the winner.
+    ///
+    /// The game is also a draw once no player can complete a line anymore, even if the board
+    /// is not full. This does not apply to the Pop Out variant, where removing tokens can
+    /// reopen lines. In Pop Out a full board is only a draw if the player to move has no token
+    /// to pop out.
+    ///
+    /// With `max_moves` set the game is a draw as soon as that many moves were played without a
+    /// winner.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::{Game, GameError, GameStatus, Player};
+    /// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.max_moves = Some(2);
+    ///
+    /// assert_eq!(game.play_move(0), Ok(GameStatus::Ongoing));
+    /// assert_eq!(game.play_move(1), Ok(GameStatus::Draw));
+    /// assert_eq!(game.play_move(2), Err(GameError::GameOver));
+    /// ```
+    pub fn status(&self) -> GameStatus {
+        self.status_at(self.position())
+    }
+
+    /// Returns the status of the game in `position`, see `status`.
+    fn status_at(&self, position: Position) -> GameStatus {
+        if let Some(winner) = self.winner_at(position) {
+            return GameStatus::Win(winner.player);
+        }
+
+        // The last side standing after eliminations wins
+        let active = self.active_players();
+        if !self.eliminated.is_empty() && active.iter().all(|player| player.is_teammate(&active[0]))
+        {
+            return GameStatus::Win(active[0].clone());
+        }
+
+        if self
+            .max_moves
+            .is_some_and(|max_moves| position.moves_played >= max_moves)
+        {
+            return GameStatus::Draw;
+        }
+
+        if self.no_moves_left_at(position) {
+            return self
+                .tiebreak_winner_at(position)
+                .map_or(GameStatus::Draw, GameStatus::Win);
+        }
+
+        if !self.pop_out
+            && !position.board.any_of_lines_still_winnable(
+                &self.line_cache.lines(position.board, self.tokens_to_win),
+                self.tokens_to_win,
+                &active,
+            )
+        {
+            return GameStatus::Draw;
+        }
+
+        GameStatus::Ongoing
+    }
+
+    /// Returns whether the player to move in `position` can't put a token anywhere.
+    fn no_moves_left_at(&self, position: Position) -> bool {
+        let board = position.board;
+        // With free placement tokens can still go in cells a dropped token can't reach
+        let full = match self.placement {
+            Placement::Gravity => board.is_board_full(),
+            Placement::Free => !board.has_empty_cell(),
+        };
+        full && (!self.pop_out || board.pop_out_lanes(&self.players[position.turn]).is_empty())
+    }
+
+    /// Returns the player who wins on the `tiebreak` rule, once the board is full without a
+    /// winning line.
+    ///
+    /// Teammates share their score. Returns None if the rule is `TieBreak::Draw`, the board is
+    /// not full yet, a line was completed, or several sides share the best score.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::game::TieBreak;
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let mut game = Game::new(3, 3, 3, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.board = Board::from_str("abb\nbaa\naab", &game.players).unwrap();
+    /// assert_eq!(game.status(), GameStatus::Draw);
+    ///
+    /// // Both have a run of two tokens
+    /// game.tiebreak = TieBreak::LongestLine;
+    /// assert_eq!(game.status(), GameStatus::Draw);
+    ///
+    /// // Alice is one token short in six lines, Bob in two
+    /// game.tiebreak = TieBreak::Threats;
+    /// assert_eq!(game.tiebreak_score(&game.players[0]), 6);
+    /// assert_eq!(game.status(), GameStatus::Win(game.players[0].clone()));
+    /// ```
+    pub fn tiebreak_winner(&self) -> Option<Player> {
+        self.tiebreak_winner_at(self.position())
+    }
+
+    /// Returns the player who wins on the `tiebreak` rule in `position`, see `tiebreak_winner`.
+    fn tiebreak_winner_at(&self, position: Position) -> Option<Player> {
+        if self.tiebreak == TieBreak::Draw
+            || !self.no_moves_left_at(position)
+            || self.winner_at(position).is_some()
+        {
+            return None;
+        }
+
+        let scores: Vec<(Player, usize)> = self
+            .active_players()
+            .into_iter()
+            .map(|player| {
+                let score = self.tiebreak_score_on(position.board, &player);
+                (player, score)
+            })
+            .collect();
+        let best = scores.iter().map(|&(_, score)| score).max()?;
+        let leaders: Vec<&Player> = scores
+            .iter()
+            .filter(|&&(_, score)| score == best)
+            .map(|(player, _)| player)
+            .collect();
+
+        leaders
+            .iter()
+            .all(|player| player.is_teammate(leaders[0]))
+            .then(|| leaders[0].clone())
+    }
+
+    /// Scores the side of `player` by the `tiebreak` rule, higher being better.
+    ///
+    /// This is the length of the longest run of the side's tokens, or the number of stretches of
+    /// `tokens_to_win` cells holding all but one of them. Only lines in `win_directions` count.
+    pub fn tiebreak_score(&self, player: &Player) -> usize {
+        self.tiebreak_score_on(&self.board, player)
+    }
+
+    /// Scores the side of `player` on `board` by the `tiebreak` rule, see `tiebreak_score`.
+    fn tiebreak_score_on(&self, board: &Board, player: &Player) -> usize {
+        let ours = |&(row, col): &(usize, usize)| {
+            board
+                .get(row, col)
+                .and_then(Option::as_ref)
+                .is_some_and(|token| token.is_teammate(player))
+        };
+        let lines = self.line_cache.lines(board, self.tokens_to_win);
+        let lines = lines
+            .iter()
+            .filter(|positions| Self::runs_in(board, positions, self.win_directions));
+
+        match self.tiebreak {
+            TieBreak::Draw => 0,
+            TieBreak::LongestLine => lines
+                .map(|line| {
+                    // Runs on a ring may continue from its last cell to its first
+                    let mut line = line.clone();
+                    let len = line.len();
+                    if board.is_ring(&line) {
+                        line.extend_from_within(..len - 1);
+                    }
+
+                    let mut longest = 0;
+                    let mut run = 0;
+                    for cell in &line {
+                        run = if ours(cell) { run + 1 } else { 0 };
+                        longest = longest.max(run.min(len));
+                    }
+                    longest
+                })
+                .max()
+                .unwrap_or(0),
+            TieBreak::Threats => lines
+                .map(|line| {
+                    let mut line = line.clone();
+                    if board.is_ring(&line) {
+                        line.extend_from_within(..self.tokens_to_win - 1);
+                    }
+
+                    line.windows(self.tokens_to_win)
+                        .filter(|window| {
+                            window.iter().filter(|cell| ours(cell)).count()
+                                == self.tokens_to_win - 1
+                        })
+                        .count()
+                })
+                .sum(),
+        }
+    }
+
+    /// Returns the number of moves played so far.
+    ///
+    /// Pop outs and placed tokens clear the history but are still counted by the statistics,
+    /// while a loaded game only brings its history along, so the larger count is used.
+    fn moves_played(&self) -> usize {
+        self.stats.total_moves().max(self.history.len())
+    }
+
+    /// Drops the current player's token into `col` and passes the turn to the next player.
+    ///
+    /// Returns the status of the game after the move.
+    ///
+    /// ## Errors
+    ///
+    /// - If the game is already over.
+    /// - If the column is out of range or full.
+    /// - If the move filter rejects the move, see `set_move_filter`.
+    pub fn play_move(&mut self, col: usize) -> Result<GameStatus, GameError> {
+        if self.status().is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        self.check_filter(col)?;
+
+        let (row, col) = self
+            .board
+            .place_token(col, self.players[self.current_turn].clone())?;
+        self.last_move = Some((row, col));
+        self.history.push((
+            self.players[self.current_turn].token.clone(),
+            self.board.lane_of(row, col),
+        ));
+
+        // A fresh move starts a new branch, so undone moves no longer apply
+        self.redo_stack.clear();
+
+        self.emit(GameEvent::TokenPlaced {
+            player: self.players[self.current_turn].clone(),
+            col,
+            row,
+        });
+        self.stats.record_move(self.current_turn);
+        self.update_center_ban();
+
+        self.next_turn();
+
+        let status = self.status();
+        self.emit_status(&status);
+        Ok(status)
+    }
+
+    /// Returns the status the game would have if the current player dropped a token into `col`,
+    /// without making the move.
+    ///
+    /// The move is checked like by `play_move` and counts towards `max_moves`. It is played on a
+    /// copy of the board, so the game itself is left untouched and no events are sent to the
+    /// observer.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let mut game = Game::new(4, 4, 3, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.board = Board::from_str("    \n    \nb   \nbaa ", &game.players).unwrap();
+    ///
+    /// assert_eq!(game.peek_move(3), Ok(GameStatus::Win(game.players[0].clone())));
+    /// assert_eq!(game.peek_move(2), Ok(GameStatus::Ongoing));
+    ///
+    /// // The last move allowed draws the game
+    /// game.max_moves = Some(1);
+    /// assert_eq!(game.peek_move(2), Ok(GameStatus::Draw));
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// - If the game is already over.
+    /// - If the column is out of range or full.
+    /// - If the move filter rejects the move, see `set_move_filter`.
+    pub fn peek_move(&self, col: usize) -> Result<GameStatus, GameError> {
+        if self.status().is_over() {
+            return Err(GameError::GameOver);
+        }
+        self.check_filter(col)?;
+
+        let mut board = self.board.clone();
+        let cell = board.place_token(col, self.current_player().clone())?;
+
+        Ok(self.status_at(Position {
+            board: &board,
+            last_move: Some(cell),
+            turn: self.turn_after(self.current_turn),
+            moves_played: self.moves_played() + 1,
+        }))
+    }
+
+    /// Returns every column the current player may drop a token into, with the status the game
+    /// would have after that move, see `peek_move`.
+    ///
+    /// This lets a front-end highlight winning moves, or a bot take a win without searching.
+    /// Columns the move filter rejects are left out, and nothing is returned once the game is
+    /// over.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// let mut game = Game::new(4, 4, 3, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.board = Board::from_str("    \n    \nb   \nbaa ", &game.players).unwrap();
+    ///
+    /// assert_eq!(
+    ///     game.legal_moves_with_status(),
+    ///     vec![
+    ///         (0, GameStatus::Ongoing),
+    ///         (1, GameStatus::Ongoing),
+    ///         (2, GameStatus::Ongoing),
+    ///         (3, GameStatus::Win(game.players[0].clone())),
+    ///     ]
+    /// );
+    /// ```
+    pub fn legal_moves_with_status(&self) -> Vec<(usize, GameStatus)> {
+        self.board
+            .open_columns()
+            .into_iter()
+            .filter_map(|col| Some((col, self.peek_move(col).ok()?)))
+            .collect()
+    }
+
+    /// Puts the current player's token in the cell at (row, column) and passes the turn on.
+    ///
+    /// Only allowed with free placement. Lines are detected just like after a drop.
+    /// Tokens put in a cell cannot be undone, so the undo and redo history is cleared.
+    ///
+    /// ## Errors
+    ///
+    /// - If free placement is disabled.
+    /// - If the game is already over.
+    /// - If the cell is out of range or already taken.
+    pub fn place_at(&mut self, row: usize, col: usize) -> Result<GameStatus, GameError> {
+        if self.placement != Placement::Free {
+            return Err(GameError::FreePlacementDisabled);
+        }
+        if self.status().is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        self.board
+            .place_at(row, col, self.players[self.current_turn].clone())?;
+        self.last_move = Some((row, col));
+        self.history.clear();
+        self.redo_stack.clear();
+
+        self.emit(GameEvent::TokenPlaced {
+            player: self.players[self.current_turn].clone(),
+            col,
+            row,
+        });
+        self.stats.record_move(self.current_turn);
+        self.update_center_ban();
+
+        self.next_turn();
+
+        let status = self.status();
+        self.emit_status(&status);
+        Ok(status)
+    }
+
+    /// Pops the current player's token out of the bottom of `col` and passes the turn on.
+    ///
+    /// Popping out can create or break lines for any player, so the status is re-evaluated.
+    /// Pop outs cannot be undone, so the undo and redo history is cleared.
+    ///
+    /// ## Errors
+    ///
+    /// - If the Pop Out variant is disabled.
+    /// - If the game is already over.
+    /// - If the bottom token of the column does not belong to the current player.
+    pub fn pop_out(&mut self, col: usize) -> Result<GameStatus, GameError> {
+        if !self.pop_out {
+            return Err(GameError::PopOutDisabled);
+        }
+        if self.status().is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        self.board.pop_out(col, &self.players[self.current_turn])?;
+        self.last_move = None;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.stats.record_move(self.current_turn);
+        self.update_center_ban();
+
+        self.next_turn();
+
+        let status = self.status();
+        self.emit_status(&status);
+        Ok(status)
+    }
+
+    /// Takes back the last move and returns the turn to the player who made it.
+    ///
+    /// ## Errors
+    ///
+    /// - If no moves have been played.
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        let (token, lane) = self.history.pop().ok_or(GameError::NothingToUndo)?;
+
+        // Pop outs clear the history, so the last move is always on top of its lane
+        if let Some((row, col)) = self.board.lane_top(lane) {
+            self.board.rows[row][col] = None;
+        }
+        self.current_turn = self.player_index(&token);
+        self.stats.undo_move(self.current_turn);
+        self.last_move = self
+            .history
+            .last()
+            .and_then(|&(_, lane)| self.board.lane_top(lane));
+        self.board.last_placed = self.last_move;
+        self.redo_stack.push((token, lane));
+        self.update_center_ban();
+
+        Ok(())
+    }
+
+    /// Plays the most recently undone move again.
+    ///
+    /// ## Errors
+    ///
+    /// - If there is no undone move, or a move has been played since the last undo.
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        let (token, lane) = self.redo_stack.pop().ok_or(GameError::NothingToRedo)?;
+
+        // play_move discards the redo stack, so keep the remaining moves aside
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        self.current_turn = self.player_index(&token);
+        let result = self.play_move(lane);
+        self.redo_stack = redo_stack;
+
+        if let Err(err) = result {
+            self.redo_stack.push((token, lane));
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Passes the turn on without a move.
+    ///
+    /// ## Errors
+    ///
+    /// - If the game is already over.
+    pub fn skip_turn(&mut self) -> Result<GameStatus, GameError> {
+        if self.status().is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        self.next_turn();
+
+        let status = self.status();
+        self.emit_status(&status);
+        Ok(status)
+    }
+
+    /// Applies the `timeout_action` for the current player, who ran out of time.
+    ///
+    /// ## Errors
+    ///
+    /// - If the game is already over.
+    pub fn time_out(&mut self) -> Result<GameStatus, GameError> {
+        match self.timeout_action {
+            TimeoutAction::Skip => self.skip_turn(),
+            TimeoutAction::Random => {
+                let open = self.board.open_columns();
+                if open.is_empty() {
+                    return Err(GameError::GameOver);
+                }
+
+                // Vary the choice with the move number so repeated timeouts differ
+                let seed =
+                    self.player_seed(self.current_turn) ^ ((self.history.len() as u64) << 32);
+                let col = open[Rng::new(seed).next_below(open.len())];
+                self.play_move(col)
+            }
+        }
+    }
+
+    /// Suggests a column for the current player.
+    ///
+    /// Prefers a move that wins right away, then one that blocks an opponent's win on their next
+    /// turn, then the open column closest to the center. Returns None if no column is open.
+    pub fn suggest_move(&self) -> Option<usize> {
+        ai::suggest_column(
+            &self.board,
+            self.current_player(),
+            &self.opponents(),
+            self.tokens_to_win,
+        )
+    }
+
+    /// Returns every drop that would win the game on the spot, as (player, column).
+    ///
+    /// Every player still in the game is checked as if it were their turn, starting with the
+    /// current player and continuing in turn order. Each open column is tried on a copy of the
+    /// board, so the game itself is left unchanged. Returns no threats once the game is over.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::{Game, Player};
+    /// let mut game = Game::new(4, 4, 3, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.board = Board::from_str("    \n    \nb   \nbaa ", &game.players).unwrap();
+    ///
+    /// let threats: Vec<(String, usize)> = game
+    ///     .immediate_threats()
+    ///     .into_iter()
+    ///     .map(|(player, col)| (player.name, col))
+    ///     .collect();
+    /// assert_eq!(
+    ///     threats,
+    ///     vec![("Alice".to_string(), 3), ("Bob".to_string(), 0)]
+    /// );
+    /// ```
+    pub fn immediate_threats(&self) -> Vec<(Player, usize)> {
+        if self.status().is_over() {
+            return Vec::new();
+        }
+
+        let mut board = self.board.clone();
+        let mut threats = Vec::new();
+
+        for player in std::iter::once(self.current_player().clone()).chain(self.opponents()) {
+            for col in board.open_columns() {
+                let Ok((row, cell_col)) = board.place_token(col, player.clone()) else {
+                    continue;
+                };
+                if Self::find_winner_at(
+                    &board,
+                    row,
+                    cell_col,
+                    self.tokens_to_win,
+                    self.exact_length,
+                    self.win_directions,
+                )
+                .is_some()
+                {
+                    threats.push((player.clone(), col));
+                }
+                board.rows[row][cell_col] = None;
+            }
+        }
+
+        threats
+    }
+
+    /// Describes the moves that would win on the spot, one warning per player.
+    fn describe_threats(&self) -> Vec<String> {
+        let threats = self.immediate_threats();
+
+        self.players
+            .iter()
+            .filter_map(|player| {
+                let columns: Vec<String> = threats
+                    .iter()
+                    .filter(|(threat, _)| threat == player)
+                    .map(|&(_, col)| self.display.labels.lane(col))
+                    .collect();
+                match columns.len() {
+                    0 => None,
+                    1 => Some(format!(
+                        "Warning: {} can win in column {}",
+                        player, columns[0]
+                    )),
+                    _ => Some(format!(
+                        "Warning: {} can win in columns {}",
+                        player,
+                        columns.join(", ")
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the player who just moved if they can now win in two or more columns, while the
+    /// player to move can't win first. Only one of the columns can be blocked, so such a fork
+    /// usually decides the game.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::{Game, Player};
+    /// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.board = Board::from_compact("......./......./......./......./..bb.../..aa...", &game.players).unwrap();
+    /// assert_eq!(game.double_threat(), None);
+    ///
+    /// // Three in a row with both ends open can be completed on either side
+    /// game.play_move(4).unwrap();
+    /// assert_eq!(game.double_threat(), Some(game.players[0].clone()));
+    /// ```
+    pub fn double_threat(&self) -> Option<Player> {
+        let (row, col) = self.last_move?;
+        let mover = self.board.rows[row][col].clone()?;
+        if mover.is_teammate(self.current_player()) {
+            return None;
+        }
+
+        let threats = self.immediate_threats();
+        if threats
+            .iter()
+            .any(|(player, _)| player.is_teammate(self.current_player()))
+        {
+            return None;
+        }
+
+        let columns: BTreeSet<usize> = threats
+            .into_iter()
+            .filter(|(player, _)| *player == mover)
+            .map(|(_, col)| col)
+            .collect();
+        (columns.len() >= 2).then_some(mover)
+    }
+
+    /// Returns every move played so far as (player token, column), oldest first.
+    ///
+    /// Undone moves are not included.
+    pub fn history(&self) -> &[(String, usize)] {
+        &self.history
+    }
+
+    /// Reconstructs a game by playing `moves` in order on a new game built from `config`.
+    ///
+    /// ## Errors
+    ///
+    /// - If the configuration is invalid.
+    /// - If a move is made out of turn, into a full or missing column, or after the game ended.
+    pub fn replay(moves: &[(String, usize)], config: GameConfig) -> Result<Game, GameError> {
+        let mut game = Game::new(
+            config.rows,
+            config.cols,
+            config.tokens_to_win,
+            config.players,
+        )?;
+        game.replay_moves(moves)?;
+
+        Ok(game)
+    }
+
+    /// Plays `moves` in order on this game, e.g. one set up with the rules of a variant.
+    ///
+    /// Returns the status of the game after the last move.
+    ///
+    /// ## Errors
+    ///
+    /// - If a move is made out of turn, into a full or missing column, or after the game ended.
+    pub fn replay_moves(&mut self, moves: &[(String, usize)]) -> Result<GameStatus, GameError> {
+        for (token, col) in moves {
+            self.play_replayed_move(token, *col)?;
+        }
+
+        Ok(self.status())
+    }
+
+    /// Plays a move from a replay, checking that it is made by the player whose turn it is.
+    fn play_replayed_move(&mut self, token: &str, col: usize) -> Result<GameStatus, GameError> {
+        if token != self.current_player().token {
+            return Err(GameError::OutOfTurn(token.to_string()));
+        }
+
+        self.play_move(col)
+    }
+
+    /// Steps through `moves` frame by frame on the game's output, waiting for Enter on its input
+    /// between moves.
+    ///
+    /// ## Errors
+    ///
+    /// - If a move is made out of turn, into a full or missing column, or after the game ended.
+    pub fn watch_replay(&mut self, moves: &[(String, usize)]) -> Result<(), GameError> {
+        for (index, (token, col)) in moves.iter().enumerate() {
+            let col = *col;
+            let status = self.play_replayed_move(token, col)?;
+
+            self.output.clear();
+            self.output.render_board(&self.board, &self.display);
+            self.output.show_message(&format!(
+                "Move {}/{}: {} played column {}",
+                index + 1,
+                moves.len(),
+                token,
+                self.display.labels.lane(col)
+            ));
+
+            match &status {
+                GameStatus::Ongoing => (),
+                GameStatus::Draw => self.output.announce_result(&status, "Draw!"),
+                GameStatus::Win(player) => {
+                    let message = self.describe_winner(player);
+                    self.output.announce_result(&status, &message);
+                }
+            }
+
+            if index + 1 < moves.len() {
+                self.output.show_message("Press Enter for the next move...");
+                if self.input.read_line().map_or(true, |line| line.is_empty()) {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the player with the given token.
+    ///
+    /// ## Panics
+    ///
+    /// - If no player has the token.
+    fn player_index(&self, token: &str) -> usize {
+        self.players
+            .iter()
+            .position(|player| player.token == token)
+            .expect("token belongs to a player")
+    }
+
+    /// Shows the last placed token falling from the edge of its lane to where it landed.
+    ///
+    /// Does nothing unless `drop_delay` is set and stdout is a terminal. The final frame is left
+    /// to the game loop, which redraws the board after every move.
+    fn animate_drop(&mut self) {
+        let (Some(delay), Some((row, col))) = (self.drop_delay, self.last_move) else {
+            return;
+        };
+        if !io::stdout().is_terminal() {
+            return;
+        }
+
+        let mut board = self.board.clone();
+        let token = board.rows[row][col].take();
+        let path = board.drop_path(row, col);
+
+        for &(path_row, path_col) in &path[..path.len() - 1] {
+            board.rows[path_row][path_col] = token.clone();
+            self.output.render_frame(&board, &self.display);
+            thread::sleep(delay);
+            board.rows[path_row][path_col] = None;
+        }
+    }
+
+    /// Starts the game loop.
+    /// The game will continue until a player wins or the game ends in a draw.
+    ///
+    /// When every player is a computer the game plays itself, pausing `move_delay` between moves.
+    /// Returns the outcome of the game, see `run_to_completion`.
+    pub fn start(&mut self) -> GameOutcome {
+        self.run_to_completion()
+    }
+
+    /// Plays the game until it is decided or quit, and returns how it ended.
+    ///
+    /// The game is shown on its output as it is played, so a program that only wants the outcome
+    /// can set a silent output first.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::board::Board;
+    /// # use connect_four::game::input::ScriptedInput;
+    /// # use connect_four::game::output::Output;
+    /// # use connect_four::game::render::DisplaySettings;
+    /// # use connect_four::game::{GameOutcome, GameResult};
+    /// # use connect_four::{Game, GameStatus, Player};
+    /// struct Silent;
+    ///
+    /// impl Output for Silent {
+    ///     fn clear(&mut self) {}
+    ///     fn render_board(&mut self, _board: &Board, _display: &DisplaySettings) {}
+    ///     fn show_message(&mut self, _message: &str) {}
+    ///     fn show_error(&mut self, _message: &str) {}
+    ///     fn announce_turn(&mut self, _player: &Player) {}
+    ///     fn announce_result(&mut self, _status: &GameStatus, _message: &str) {}
+    /// }
+    ///
+    /// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+    /// game.set_output(Box::new(Silent));
+    /// game.set_input(Box::new(ScriptedInput::new(["0", "1", "0", "1", "0", "1", "0"])));
+    /// let outcome = game.run_to_completion();
+    ///
+    /// assert_eq!(
+    ///     outcome.result,
+    ///     GameResult::Winner(game.players[0].clone(), vec![(2, 0), (3, 0), (4, 0), (5, 0)])
+    /// );
+    /// assert_eq!(outcome.history.len(), 7);
+    /// ```
+    pub fn run_to_completion(&mut self) -> GameOutcome {
+        let mut status = self.status();
+        self.emit_status(&status);
+
+        let spectated = self.is_spectated();
+
+        // Messages shown below the board after the output is cleared
+        let mut notice: Option<String> = None;
+        let mut error: Option<String> = None;
+
+        loop {
+            self.output.clear();
+
+            if self.show_threats {
+                for warning in self.describe_threats() {
+                    self.output.show_message(&warning);
+                }
+            }
+
+            self.output.render_board(&self.board, &self.display);
+
+            if let Some((row, col)) = self.last_move {
+                self.output.show_message(&format!(
+                    "Last move: column {}, row {}",
+                    self.display.labels.lane(col),
+                    self.display.labels.row(row)
+                ));
+            }
+
+            if self.commentary {
+                if let Some(player) = self.double_threat() {
+                    self.output.show_message(&format!(
+                        "{} has a double threat — {} can only block one!",
+                        player.name,
+                        self.current_player().name
+                    ));
+                }
+            }
+
+            if let Some(notice) = notice.take() {
+                self.output.show_message(&notice);
+            }
+            if let Some(error) = error.take() {
+                self.output.show_error(&error);
+            }
+
+            let result = match &status {
+                GameStatus::Ongoing => None,
+                GameStatus::Draw => Some("Draw!".to_string()),
+                GameStatus::Win(player) if self.tiebreak_winner().is_some() => Some(format!(
+                    "{}\nDecided on tie-break: {}",
+                    self.describe_winner(player),
+                    match self.tiebreak {
+                        TieBreak::LongestLine => format!(
+                            "the longest line, with {} tokens",
+                            self.tiebreak_score(player)
+                        ),
+                        _ => format!(
+                            "the most lines one token short, with {}",
+                            self.tiebreak_score(player)
+                        ),
+                    }
+                )),
+                GameStatus::Win(player) => Some(self.describe_winner(player)),
+            };
+            if let Some(result) = result {
+                let message = format!("{}\n{}", result, self.summary());
+                self.output.announce_result(&status, &message);
+
+                return self.outcome(match status {
+                    GameStatus::Win(player) => {
+                        let line = self.find_winner().map(|line| line.cells);
+                        GameResult::Winner(player, line.unwrap_or_default())
+                    }
+                    _ => GameResult::Draw,
+                });
+            }
+
+            self.output.announce_move(
+                &self.players[self.current_turn],
+                self.moves_played() + 1,
+                self.round,
+            );
+
+            if self.pop_out {
+                self.output.show_message(
+                    "Enter 'p<column>' to pop out one of your tokens from the bottom row.",
+                );
+            }
+            if self.placement == Placement::Free {
+                self.output
+                    .show_message("Enter '<row>,<column>' to put your token in any empty cell.");
+            }
+
+            // Give spectators a moment to follow the game
+            if spectated {
+                thread::sleep(self.move_delay);
+            }
+
+            let opponents = self.opponents();
+            let thinking = Instant::now();
+            let mut console = Console {
+                input: self.input.as_mut(),
+                output: self.output.as_mut(),
+                display: &self.display,
+            };
+            let command = self.strategies[self.current_turn].choose_command_from(
+                &mut console,
+                &self.board,
+                &self.players[self.current_turn],
+                &opponents,
+                self.tokens_to_win,
+            );
+            let think_time = thinking.elapsed();
+            let (thinker, is_move) = (
+                self.current_turn,
+                matches!(
+                    command,
+                    Command::Drop(_) | Command::PopOut(_) | Command::Place(..)
+                ),
+            );
+
+            // Computer players don't know the custom rule, so they are moved to a column it allows
+            let command = match command {
+                Command::Drop(col)
+                    if !self.strategies[self.current_turn].is_interactive()
+                        && !self.filter_allows(col) =>
+                {
+                    let open = self.board.open_columns();
+                    let allowed = open.into_iter().find(|&col| self.filter_allows(col));
+                    Command::Drop(allowed.unwrap_or(col))
+                }
+                command => command,
+            };
+
+            let result = match command {
+                Command::Drop(col) => self.play_move(col).inspect(|_| self.animate_drop()),
+                Command::Undo => self.undo().map(|_| self.status()),
+                Command::Redo => self.redo().map(|_| self.status()),
+                Command::PopOut(col) => self.pop_out(col),
+                Command::Place(row, col) => self.place_at(row, col),
+                Command::Quit => {
+                    self.output.show_message("Game aborted");
+                    return self.outcome(GameResult::Aborted);
+                }
+                Command::TimedOut => {
+                    notice = Some(format!("{} ran out of time.", self.current_player().name));
+                    self.time_out()
+                }
+                Command::Forfeit => {
+                    notice = Some(format!("{} forfeited.", self.current_player().name));
+                    self.forfeit()
+                }
+                Command::Hint => {
+                    notice = Some(match self.suggest_move() {
+                        Some(col) => {
+                            format!("Hint: try column {}", self.display.labels.lane(col))
+                        }
+                        None => "No moves left.".to_string(),
+                    });
+                    continue;
+                }
+                Command::Save(path) => {
+                    // A failed save is no reason to end the game
+                    match self.board.save(&path) {
+                        Ok(()) => notice = Some(format!("Board saved to {}", path.display())),
+                        Err(err) => {
+                            error = Some(format!(
+                                "Could not save the board to {}: {}",
+                                path.display(),
+                                err
+                            ))
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            // Only moves that were played count, not commands or rejected moves
+            if is_move && result.is_ok() {
+                self.stats.record_think_time(thinker, think_time);
+                if self
+                    .slow_move
+                    .is_some_and(|slow_move| think_time > slow_move)
+                {
+                    notice = Some(format!(
+                        "{} took {:.2}s to move.",
+                        self.players[thinker].name,
+                        think_time.as_secs_f64()
+                    ));
+                }
+            }
+
+            status = match result {
+                Ok(status) => status,
+                Err(err) => {
+                    error = Some(err.describe(&self.display.labels));
+                    GameStatus::Ongoing
+                }
+            };
+        }
+    }
+
+    /// Returns the outcome of the game with `result` and the moves played so far.
+    fn outcome(&self, result: GameResult) -> GameOutcome {
+        GameOutcome {
+            result,
+            history: self.history.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::{BoardRow, Gravity};
+    use std::hint::black_box;
+
+    /// Everything a board holds, to compare it before and after a move is peeked at.
+    type BoardSnapshot = (
+        Vec<BoardRow>,
+        BTreeSet<(usize, usize)>,
+        Option<(usize, usize)>,
+        Option<(usize, usize)>,
+        Gravity,
+        bool,
+    );
+
+    /// Returns everything `board` holds.
+    fn snapshot(board: &Board) -> BoardSnapshot {
+        (
+            board.rows.clone(),
+            board.blocked.clone(),
+            board.last_placed,
+            board.ghost,
+            board.gravity,
+            board.wrap,
+        )
+    }
+
+    /// Peeks at every column and checks that the game is unchanged afterwards, and that each
+    /// peeked status is the one playing the move leads to.
+    fn assert_peeks_match_moves(game: &Game) {
+        let board = snapshot(&game.board);
+        let (turn, history, moves) = (game.current_turn, game.history.clone(), game.moves_played());
+
+        for col in 0..game.board.lane_count() {
+            let peeked = game.peek_move(col);
+
+            assert_eq!(
+                snapshot(&game.board),
+                board,
+                "column {} changed the board",
+                col
+            );
+            assert_eq!(game.current_turn, turn);
+            assert_eq!(game.history, history);
+            assert_eq!(game.moves_played(), moves);
+
+            let mut played = Game::new(
+                game.board.rows.len(),
+                game.board.rows[0].len(),
+                game.tokens_to_win,
+                game.players.clone(),
+            )
+            .unwrap();
+            played.board = game.board.clone();
+            played.last_move = game.last_move;
+            played.current_turn = game.current_turn;
+            played.max_moves = game.max_moves.map(|max_moves| max_moves - moves);
+            assert_eq!(peeked, played.play_move(col), "column {}", col);
+        }
+    }
+
+    /// Creates a game of two players on an empty board.
+    fn game(rows: usize, cols: usize, tokens_to_win: usize) -> Game {
+        Game::new(
+            rows,
+            cols,
+            tokens_to_win,
+            vec![
+                Player::with_token("Alice", 'a'),
+                Player::with_token("Bob", 'b'),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn find_winner_at_only_reads_the_lines_through_the_cell() {
+        let mut game = game(20, 20, 4);
+        for row in 0..20 {
+            for col in 0..20 {
+                game.board.rows[row][col] = Some(game.players[(row + col / 2) % 2].clone());
+            }
+        }
+        // A line far from the cell checked is only found by a full scan
+        for col in 0..4 {
+            game.board.rows[0][col] = Some(game.players[0].clone());
+        }
+        let board = &game.board;
+
+        assert!(Game::find_winner_on(board, 4, false, WinDirections::ALL).is_some());
+        assert_eq!(
+            Game::find_winner_at(board, 10, 10, 4, false, WinDirections::ALL),
+            None
+        );
+    }
+
+    #[test]
+    #[ignore = "compares timings, which are unreliable on a busy machine"]
+    fn find_winner_at_is_much_faster_than_a_full_scan_on_a_large_board() {
+        let mut game = game(20, 20, 4);
+        // Pairs of tokens that alternate every row and every two columns never line up four
+        for row in 0..20 {
+            for col in 0..20 {
+                game.board.rows[row][col] = Some(game.players[(row + col / 2) % 2].clone());
+            }
+        }
+        let board = &game.board;
+        assert_eq!(
+            Game::find_winner_on(board, 4, false, WinDirections::ALL),
+            None
+        );
+
+        let time = |scan: &dyn Fn() -> Option<WinningLine>| {
+            let started = Instant::now();
+            for _ in 0..200 {
+                assert_eq!(black_box(scan()), None);
+            }
+            started.elapsed()
+        };
+        let full_scan = time(&|| Game::find_winner_on(board, 4, false, WinDirections::ALL));
+        let last_move = time(&|| Game::find_winner_at(board, 10, 10, 4, false, WinDirections::ALL));
+
+        // Only a few dozen cells around the last move should be read, against all 400
+        assert!(
+            last_move * 50 < full_scan,
+            "checking the last move took {:?}, a full scan {:?}",
+            last_move,
+            full_scan
+        );
+    }
+
+    #[test]
+    fn peek_move_leaves_the_board_unchanged() {
+        let mut game = game(4, 4, 3);
+        game.board = Board::from_str("    \n    \nb   \nbaa ", &game.players).unwrap();
+        game.play_move(1).unwrap();
+        assert_peeks_match_moves(&game);
+    }
+
+    #[test]
+    fn peek_move_leaves_a_wrapping_board_unchanged() {
+        let mut game = game(3, 6, 4);
+        game.board = Board::from_str("      \n      \naa b a", &game.players).unwrap();
+        game.board.wrap = true;
+        assert_eq!(
+            game.peek_move(4),
+            Ok(GameStatus::Win(game.players[0].clone()))
+        );
+        assert_peeks_match_moves(&game);
+    }
+
+    #[test]
+    fn peek_move_leaves_blocked_cells_unchanged() {
+        let mut game = game(4, 4, 3);
+        game.board = Board::from_str("    \n #  \n a# \nbab#", &game.players).unwrap();
+        assert_eq!(game.peek_move(3), Ok(GameStatus::Ongoing));
+        assert_peeks_match_moves(&game);
+    }
+
+    #[test]
+    fn peek_move_leaves_a_board_with_sideways_gravity_unchanged() {
+        let mut game = game(4, 5, 3);
+        game.board.gravity = Gravity::Left;
+        game.play_move(0).unwrap();
+        game.play_move(1).unwrap();
+        game.play_move(0).unwrap();
+        assert_peeks_match_moves(&game);
+    }
+
+    #[test]
+    fn peek_move_counts_the_move_towards_max_moves() {
+        let mut game = game(6, 7, 4);
+        game.max_moves = Some(3);
+        game.play_move(0).unwrap();
+        game.play_move(1).unwrap();
+
+        assert_eq!(game.peek_move(2), Ok(GameStatus::Draw));
+        assert_peeks_match_moves(&game);
+        assert_eq!(game.play_move(2), Ok(GameStatus::Draw));
+        assert_eq!(game.peek_move(3), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn peek_move_applies_the_move_filter() {
+        let mut game = game(6, 7, 4);
+        game.set_move_filter(Box::new(|_, col, _, _| col != 3), "Not the center.");
+
+        assert_eq!(
+            game.peek_move(3),
+            Err(GameError::MoveRejected("Not the center.".to_string()))
+        );
+        assert_eq!(game.peek_move(2), Ok(GameStatus::Ongoing));
+        assert_eq!(
+            game.peek_move(7),
+            Err(GameError::InvalidMove(BoardError::ColumnOutOfRange(7)))
+        );
+    }
+
+    #[test]
+    fn legal_moves_with_status_finds_the_winning_move() {
+        let mut game = game(6, 7, 4);
+        game.board = Board::from_compact(
+            "......./......./......./......./bb...../aaa.b..",
+            &game.players,
+        )
+        .unwrap();
+        let alice = game.players[0].clone();
+
+        let moves = game.legal_moves_with_status();
+        assert_eq!(moves.len(), 7);
+        assert_eq!(moves[3], (3, GameStatus::Win(alice)));
+        assert!(moves
+            .iter()
+            .filter(|&&(col, _)| col != 3)
+            .all(|(_, status)| *status == GameStatus::Ongoing));
+    }
+
+    #[test]
+    fn legal_moves_with_status_reports_the_draw_at_max_moves() {
+        let mut game = game(6, 7, 4);
+        game.max_moves = Some(7);
+        for col in [0, 0, 1, 1, 2, 2] {
+            game.play_move(col).unwrap();
+        }
+
+        // Alice wins in column 3 before the cap, every other move uses it up
+        let moves = game.legal_moves_with_status();
+        assert_eq!(moves[3], (3, GameStatus::Win(game.players[0].clone())));
+        assert!(moves
+            .iter()
+            .filter(|&&(col, _)| col != 3)
+            .all(|(_, status)| *status == GameStatus::Draw));
+    }
+
+    #[test]
+    fn legal_moves_with_status_leaves_out_full_and_rejected_columns() {
+        let mut game = game(2, 3, 2);
+        game.play_move(0).unwrap();
+        game.play_move(0).unwrap();
+        game.set_move_filter(Box::new(|_, col, _, _| col != 1), "Not column 1.");
+
+        assert_eq!(
+            game.legal_moves_with_status(),
+            vec![(2, GameStatus::Ongoing)]
+        );
+    }
+
+    #[test]
+    fn handicap_tokens_are_not_moves() {
+        let mut game = game(6, 7, 4);
+        game.max_moves = Some(1);
+        game.set_move_filter(Box::new(|_, col, _, _| col != 3), "Not column 3.");
+        game.apply_handicap(&[(1, 3), (1, 3)]).unwrap();
+
+        assert_eq!(game.board.rows[4][3], Some(game.players[1].clone()));
+        assert_eq!(game.board.rows[5][3], Some(game.players[1].clone()));
+        assert_eq!(game.board.last_placed, None);
+        assert!(game.history().is_empty());
+        assert_eq!(game.stats().total_moves(), 0);
+        assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+        assert_eq!(game.current_turn(), 0);
+        // Only the first real move uses up the cap
+        assert_eq!(game.status(), GameStatus::Ongoing);
+        assert_eq!(game.play_move(0), Ok(GameStatus::Draw));
+    }
+
+    #[test]
+    fn the_center_is_banned_on_the_first_move_after_a_handicap() {
+        let mut game = game(6, 7, 4);
+        game.set_ban_first_center(true);
+        // Handicap tokens may go in the center, and don't count as the first move
+        game.apply_handicap(&[(1, 3), (1, 0)]).unwrap();
+
+        let banned = Err(GameError::InvalidMove(BoardError::CenterBanned(3)));
+        assert_eq!(game.play_move(3), banned);
+        assert_eq!(game.play_move(2), Ok(GameStatus::Ongoing));
+        assert!(game.board.valid_move(3));
+
+        game.undo().unwrap();
+        assert_eq!(game.play_move(3), banned);
+    }
+
+    #[test]
+    fn rejected_handicap_leaves_the_game_unchanged() {
+        let mut game = game(6, 7, 4);
+        let empty = snapshot(&game.board);
+
+        assert_eq!(
+            game.apply_handicap(&[(1, 3), (1, 9)]),
+            Err(GameError::InvalidMove(BoardError::ColumnOutOfRange(9)))
+        );
+        assert_eq!(
+            game.apply_handicap(&[(0, 3), (5, 4)]),
+            Err(GameError::NoSuchPlayer(5))
+        );
+        assert_eq!(
+            game.apply_handicap(&[(0, 0), (0, 1), (0, 2), (0, 3)]),
+            Err(GameError::HandicapEndsGame)
+        );
+        assert_eq!(snapshot(&game.board), empty);
+    }
+
+    #[test]
+    fn a_full_board_without_a_line_is_a_draw() {
+        // Two players can't fill two columns without a pair touching, so four take turns
+        let players = ['a', 'b', 'c', 'd']
+            .map(|token| Player::with_token(token.to_string(), token))
+            .to_vec();
+        let mut game = Game::new(4, 2, 2, players).unwrap();
+        game.board = Board::from_str("ab\ncd\nab\ncd", &game.players).unwrap();
+
+        assert!(game.board.is_board_full());
+        assert_eq!(game.status(), GameStatus::Draw);
+    }
+
+    #[test]
+    fn a_board_with_room_for_a_line_is_ongoing() {
+        let mut game = game(3, 2, 2);
+        game.board = Board::from_str("  \n  \nab", &game.players).unwrap();
+
+        assert!(!game.board.is_board_full());
+        assert_eq!(game.status(), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn a_wide_short_board_is_won_across() {
+        assert_eq!(
+            Game::new(4, 10, 11, game(4, 10, 5).players).err(),
+            Some(GameError::TokensToWinTooLarge)
+        );
+
+        let mut game = game(4, 10, 5);
+        // Bob fills the last column while Alice lines up along the bottom
+        for col in [0, 9, 1, 9, 2, 9, 3, 9] {
+            assert_eq!(game.play_move(col), Ok(GameStatus::Ongoing));
+        }
+
+        assert_eq!(
+            game.play_move(4),
+            Ok(GameStatus::Win(game.players[0].clone()))
+        );
+    }
+
+    #[test]
+    fn a_full_column_is_too_short_to_win_on_a_wide_short_board() {
+        let mut game = game(4, 10, 5);
+        game.board = Board::from_str(
+            "a        b\na        b\na        b\na        b",
+            &game.players,
+        )
+        .unwrap();
+
+        assert_eq!(game.find_winner(), None);
+        assert_eq!(game.status(), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn a_win_on_the_move_that_fills_the_board_is_not_a_draw() {
+        let mut game = game(3, 3, 3);
+        game.board = Board::from_str(" bb\nbab\naba", &game.players).unwrap();
+        let alice = game.players[0].clone();
+        assert_eq!(game.status(), GameStatus::Ongoing);
+
+        // The last empty cell completes Alice's diagonal
+        assert_eq!(game.play_move(0), Ok(GameStatus::Win(alice.clone())));
+        assert!(game.board.is_board_full());
+        assert_eq!(game.status(), GameStatus::Win(alice));
+    }
+
+    #[test]
+    fn tokens_piled_against_a_side_wall_win() {
+        for (gravity, col) in [(Gravity::Left, 0), (Gravity::Right, 5)] {
+            let mut game = game(5, 6, 4);
+            game.board.gravity = gravity;
+            // Alice fills the first four rows along the wall, Bob stacks up next to her
+            for row in [0, 0, 1, 1, 2, 2] {
+                assert_eq!(game.play_move(row), Ok(GameStatus::Ongoing));
+            }
+
+            assert_eq!(
+                game.play_move(3),
+                Ok(GameStatus::Win(game.players[0].clone()))
+            );
+            let line = game.find_winner().unwrap();
+            assert_eq!(line.cells, vec![(0, col), (1, col), (2, col), (3, col)]);
+        }
+    }
+
+    /// Returns the tokens assigned to `count` players without one.
+    fn assigned_tokens(count: usize) -> Result<Vec<String>, GameError> {
+        let players = (0..count)
+            .map(|index| Player::new(format!("Player {}", index)))
+            .collect();
+        let game = Game::new(20, 20, 2, players)?;
+        Ok(game
+            .players
+            .into_iter()
+            .map(|player| player.token)
+            .collect())
+    }
+
+    #[test]
+    fn tokens_are_assigned_afresh_for_every_game() {
+        assert_eq!(
+            assigned_tokens(3),
+            Ok(vec!["a".into(), "b".into(), "c".into()])
+        );
+        assert_eq!(assigned_tokens(2), Ok(vec!["a".into(), "b".into()]));
+    }
+
+    #[test]
+    fn tokens_run_on_past_the_alphabet() {
+        let tokens = assigned_tokens(62).unwrap();
+        assert_eq!(tokens[25], "z");
+        assert_eq!(tokens[26], "A");
+        assert_eq!(tokens[52], "0");
+        assert_eq!(tokens[61], "9");
+        assert_eq!(assigned_tokens(63), Err(GameError::TooManyPlayers));
+    }
+
+    #[test]
+    fn assigned_tokens_skip_the_ones_players_chose() {
+        let players = vec![Player::new("Alice"), Player::with_token("Bob", 'a')];
+        let game = Game::new(6, 7, 4, players).unwrap();
+
+        assert_eq!(game.players[0].token, "b");
+        assert_eq!(game.players[1].token, "a");
+    }
+
+    #[test]
+    fn check_line_with_exact_length_ignores_overlines() {
+        let alice = Player::with_token("Alice", 'a');
+        let run = |len: usize| {
+            let mut line = vec![None; 7];
+            line[1..=len].fill(Some(alice.clone()));
+            line
+        };
+
+        assert_eq!(
+            Game::check_line(&run(4), 4, true),
+            Some((alice.clone(), 1, 4))
+        );
+        assert_eq!(Game::check_line(&run(5), 4, true), None);
+        assert_eq!(Game::check_line(&run(5), 4, false), Some((alice, 1, 5)));
+    }
+
+    #[test]
+    fn a_move_joining_two_runs_into_an_overline_does_not_win_with_exact_length() {
+        for (bottom, col, wins) in [("aa aa  ", 2, false), ("aaa    ", 3, true)] {
+            let mut game = game(6, 7, 4);
+            game.exact_length = true;
+            // Bob's tokens sit on top of Alice's without forming a line
+            let above = bottom.replace('a', "b");
+            let board = format!("{}{}\n{}", "       \n".repeat(4), above, bottom);
+            game.board = Board::from_str(&board, &game.players).unwrap();
+
+            let expected = if wins {
+                GameStatus::Win(game.players[0].clone())
+            } else {
+                GameStatus::Ongoing
+            };
+            assert_eq!(game.play_move(col), Ok(expected), "{}", bottom);
+        }
+    }
+
+    #[test]
+    fn replayed_moves_follow_the_rules_the_game_was_set_up_with() {
+        let moves: Vec<(String, usize)> = [("a", 5), ("b", 4), ("a", 5), ("b", 4), ("a", 5)]
+            .map(|(token, lane)| (token.to_string(), lane))
+            .to_vec();
+        let mut game = game(6, 7, 3);
+        game.board.gravity = Gravity::Left;
+
+        // Alice's tokens pile up against the left wall of the bottom row
+        assert_eq!(
+            game.replay_moves(&moves),
+            Ok(GameStatus::Win(game.players[0].clone()))
+        );
+        assert_eq!(
+            game.find_winner().unwrap().cells,
+            vec![(5, 0), (5, 1), (5, 2)]
+        );
     }
 }
@@ -0,0 +1,127 @@
+//! Input module
+//!
+//! Contains the `InputSource` trait, which supplies the lines typed by human players, and the
+//! `StdinInput` that reads them from the terminal.
+//!
+//! A `Game` passes its input source to the strategy of each player, so the same game loop can be
+//! fed from stdin, from a script in tests, or from a remote connection.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Supplies the lines human players type, e.g. a column or a command like `u`.
+pub trait InputSource {
+    /// Reads the next line.
+    ///
+    /// Returns an empty string once input is closed.
+    fn read_line(&mut self) -> io::Result<String>;
+
+    /// Reads the next line, waiting at most `timeout`.
+    ///
+    /// Returns None if no line arrived in time. Defaults to `read_line`, for sources that never
+    /// keep a player waiting.
+    fn read_line_timeout(&mut self, timeout: Duration) -> Option<io::Result<String>> {
+        let _ = timeout;
+        Some(self.read_line())
+    }
+}
+
+impl Default for Box<dyn InputSource> {
+    fn default() -> Self {
+        Box::new(StdinInput::default())
+    }
+}
+
+/// Reads lines from stdin.
+///
+/// A read that timed out can't be cancelled, so it carries on in the background and its line is
+/// returned by the next read. This keeps every line typed on stdin in order, as long as the same
+/// `StdinInput` is used for the whole session.
+#[derive(Debug, Default)]
+pub struct StdinInput {
+    /// A line still being read after a read timed out.
+    pending: Option<Receiver<io::Result<String>>>,
+}
+
+impl InputSource for StdinInput {
+    fn read_line(&mut self) -> io::Result<String> {
+        if let Some(receiver) = self.pending.take() {
+            return receiver.recv().unwrap_or_else(|_| Ok(String::new()));
+        }
+
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        Ok(line)
+    }
+
+    fn read_line_timeout(&mut self, timeout: Duration) -> Option<io::Result<String>> {
+        let receiver = self.pending.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let mut line = String::new();
+                let result = io::stdin().read_line(&mut line).map(|_| line);
+                // The receiver is only dropped once a line was received
+                let _ = sender.send(result);
+            });
+            receiver
+        });
+
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => {
+                self.pending = None;
+                Some(result)
+            }
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                self.pending = None;
+                Some(Ok(String::new()))
+            }
+        }
+    }
+}
+
+/// Supplies a fixed list of lines, e.g. to script a game in tests.
+///
+/// Input is closed once every line was read.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::input::ScriptedInput;
+/// # use connect_four::{Game, GameStatus, Player};
+/// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+/// game.set_input(Box::new(ScriptedInput::new(["0", "1", "0", "1", "0", "1", "0"])));
+/// game.start();
+///
+/// assert_eq!(game.status(), GameStatus::Win(game.players[0].clone()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedInput {
+    lines: VecDeque<String>,
+}
+
+impl ScriptedInput {
+    /// Creates an input source that supplies `lines` in order.
+    pub fn new<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn read_line(&mut self) -> io::Result<String> {
+        Ok(self
+            .lines
+            .pop_front()
+            .map(|line| line + "\n")
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,164 @@
+//! Match module
+//!
+//! Contains the `Match` struct, which plays a series of games between the same players.
+//!
+//! Each round is a regular `Game`. The starting player rotates every round, and the match keeps
+//! score of the wins of each player and the number of draws.
+
+use super::input::InputSource;
+use super::output::Output;
+use super::{Game, GameError, GameStatus, Player};
+
+/// Represents a match of several rounds between the same players.
+pub struct Match {
+    /// The number of rounds to play.
+    rounds: usize,
+    /// Creates the game for each round.
+    setup: Box<dyn Fn() -> Result<Game, GameError>>,
+    /// The players of the match, known once the first round is set up.
+    players: Vec<Player>,
+    /// The number of rounds won by each player.
+    wins: Vec<usize>,
+    /// The number of rounds that ended in a draw.
+    draws: usize,
+    /// Supplies the lines typed by human players, passed from round to round.
+    input: Box<dyn InputSource>,
+    /// Shows the scoreboard, taken over from the game of the last round.
+    output: Box<dyn Output>,
+}
+
+impl Match {
+    /// Creates a new match of `rounds` rounds.
+    ///
+    /// `setup` creates the game for each round, so every round starts from the same configuration.
+    pub fn new<F>(rounds: usize, setup: F) -> Self
+    where
+        F: Fn() -> Result<Game, GameError> + 'static,
+    {
+        Self {
+            rounds,
+            setup: Box::new(setup),
+            players: Vec::new(),
+            wins: Vec::new(),
+            draws: 0,
+            input: Box::default(),
+            output: Box::default(),
+        }
+    }
+
+    /// Sets where the lines typed by human players are read from, for every round.
+    ///
+    /// Input is read from stdin by default.
+    pub fn set_input(&mut self, input: Box<dyn InputSource>) {
+        self.input = input;
+    }
+
+    /// Returns the number of rounds won by each player, in player order.
+    pub fn wins(&self) -> &[usize] {
+        &self.wins
+    }
+
+    /// Returns the number of rounds that ended in a draw.
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    /// Plays the rounds of the match, showing the scoreboard after each one.
+    ///
+    /// The scoreboard is shown on the output of the round's game, so it is printed, recorded or
+    /// kept quiet like the game itself.
+    ///
+    /// After every round but the last the players are asked whether to continue, unless no
+    /// player is interactive.
+    ///
+    /// ## Errors
+    ///
+    /// - If a round's game can't be created.
+    pub fn play(&mut self) -> Result<(), GameError> {
+        for round in 0..self.rounds {
+            let mut game = (self.setup)()?;
+
+            if self.players.is_empty() {
+                self.players = game.players.clone();
+                self.wins = vec![0; self.players.len()];
+            }
+
+            // Rotate the starting player every round, starting from the configured one
+            game.set_first_player((game.current_turn() + round) % self.players.len())?;
+            game.round = Some(round + 1);
+            // Every round reads from the same input, so no typed line is lost between rounds
+            game.set_input(std::mem::take(&mut self.input));
+            game.start();
+            self.input = game.take_input();
+            self.output = game.take_output();
+
+            match game.status() {
+                // A team win counts for every member of the team
+                GameStatus::Win(winner) => {
+                    for (player, wins) in self.players.iter().zip(&mut self.wins) {
+                        if player.is_teammate(&winner) {
+                            *wins += 1;
+                        }
+                    }
+                }
+                GameStatus::Draw => self.draws += 1,
+                // The round was aborted
+                GameStatus::Ongoing => break,
+            }
+
+            let scoreboard = self.scoreboard(round + 1);
+            self.output.show_message(&format!("\n{}", scoreboard));
+
+            // Nobody needs to be asked when only computers are playing
+            if round + 1 < self.rounds && !game.is_spectated() && !self.confirm_next_round() {
+                break;
+            }
+        }
+
+        let result = self.result();
+        self.output.show_message(&result);
+        Ok(())
+    }
+
+    /// Renders the score of each player after `played` rounds.
+    fn scoreboard(&self, played: usize) -> String {
+        let mut output = format!("Scoreboard after round {} of {}:\n", played, self.rounds);
+
+        for (player, wins) in self.players.iter().zip(&self.wins) {
+            output.push_str(&format!("  {}: {}\n", player, wins));
+        }
+
+        output.push_str(&format!("  Draws: {}", self.draws));
+        output
+    }
+
+    /// Describes the winner of the match, or a tie between the leading players.
+    fn result(&self) -> String {
+        let best = self.wins.iter().copied().max().unwrap_or(0);
+        let leaders: Vec<&Player> = self
+            .players
+            .iter()
+            .zip(&self.wins)
+            .filter(|(_, &wins)| wins == best)
+            .map(|(player, _)| player)
+            .collect();
+
+        match leaders.as_slice() {
+            [winner] => format!("{} wins the match!", winner),
+            _ => "The match is a tie!".to_string(),
+        }
+    }
+
+    /// Asks whether to play the next round. Anything but `n` or `q` continues.
+    fn confirm_next_round(&mut self) -> bool {
+        self.output.show_message("Play the next round? [Y/n]");
+
+        match self.input.read_line() {
+            Ok(input_line) if !input_line.is_empty() => {
+                let input = input_line.trim().to_lowercase();
+                !matches!(input.as_str(), "n" | "no" | "q" | "quit")
+            }
+            _ => false,
+        }
+    }
+}
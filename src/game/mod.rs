@@ -1,4 +1,5 @@
 // Declare the `game.rs` file as a module
+#[allow(clippy::module_inception)]
 pub mod game;
 // Declare the `board.rs` file as a module
 pub mod board;
@@ -6,7 +7,46 @@ pub mod board;
 pub mod player;
 // Declare the `util.rs` file as a module
 pub mod util;
+// Declare the `strategy.rs` file as a module
+pub mod strategy;
+// Declare the `ai.rs` file as a module
+pub mod ai;
+// Declare the `tui.rs` file as a module
+pub mod tui;
+// Declare the `match_play.rs` file as a module
+pub mod match_play;
+// Declare the `rng.rs` file as a module
+pub mod rng;
+// Declare the `event.rs` file as a module
+pub mod event;
+// Declare the `config.rs` file as a module
+pub mod config;
+// Declare the `setup.rs` file as a module
+pub mod setup;
+// Declare the `self_play.rs` file as a module
+pub mod self_play;
+// Declare the `render.rs` file as a module
+pub mod render;
+// Declare the `stats.rs` file as a module
+pub mod stats;
+// Declare the `input.rs` file as a module
+pub mod input;
+// Declare the `output.rs` file as a module
+pub mod output;
+// Declare the `cast.rs` file as a module
+pub mod cast;
+// Declare the `roster.rs` file as a module
+pub mod roster;
+// Declare the `protocol.rs` file as a module
+#[cfg(feature = "serde")]
+pub mod protocol;
+// Declare the `records.rs` file as a module
+#[cfg(feature = "serde")]
+pub mod records;
 
 // Re-export key types for easier access
-pub use game::Game;
+pub use game::{
+    Game, GameConfig, GameError, GameOutcome, GameResult, GameStatus, MoveFilter, Placement,
+    TieBreak, TimeoutAction, WinDirections, WinningLine,
+};
 pub use player::Player;
@@ -0,0 +1,255 @@
+//! Output module
+//!
+//! Contains the `Output` trait, which shows a game to its players, the `StdoutOutput` that
+//! prints it to the terminal, and the `QuietOutput` that prints it as a plain log.
+//!
+//! `Game::start` only talks to its output, so a networked or graphical front-end can receive the
+//! same calls the terminal does, and tests can capture them.
+
+use super::board::Board;
+use super::render::{self, DisplaySettings};
+use super::util;
+use super::{GameStatus, Player};
+use crossterm::cursor::{MoveToPreviousLine, RestorePosition, SavePosition};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+/// Shows a game to its players.
+///
+/// Every turn the output is cleared and the board is rendered, followed by any messages.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::board::Board;
+/// # use connect_four::game::input::ScriptedInput;
+/// # use connect_four::game::output::Output;
+/// # use connect_four::game::render::DisplaySettings;
+/// # use connect_four::{Game, GameStatus, Player};
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// /// Keeps the errors shown to the players.
+/// struct Errors(Rc<RefCell<Vec<String>>>);
+///
+/// impl Output for Errors {
+///     fn clear(&mut self) {}
+///     fn render_board(&mut self, _board: &Board, _display: &DisplaySettings) {}
+///     fn show_message(&mut self, _message: &str) {}
+///     fn show_error(&mut self, message: &str) {
+///         self.0.borrow_mut().push(message.to_string());
+///     }
+///     fn announce_turn(&mut self, _player: &Player) {}
+///     fn announce_result(&mut self, _status: &GameStatus, _message: &str) {}
+/// }
+///
+/// let errors = Rc::new(RefCell::new(Vec::new()));
+/// let mut game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+/// game.set_output(Box::new(Errors(errors.clone())));
+/// game.set_input(Box::new(ScriptedInput::new(["u"])));
+/// game.start();
+///
+/// assert_eq!(*errors.borrow(), vec!["There is no move to undo.".to_string()]);
+/// ```
+pub trait Output {
+    /// Clears everything shown so far, before the next turn is shown.
+    fn clear(&mut self);
+
+    /// Shows the board as set out by `display`.
+    fn render_board(&mut self, board: &Board, display: &DisplaySettings);
+
+    /// Shows a frame of an animation, e.g. a token falling through the board.
+    ///
+    /// Defaults to clearing the output and rendering the board, for outputs that redraw.
+    fn render_frame(&mut self, board: &Board, display: &DisplaySettings) {
+        self.clear();
+        self.render_board(board, display);
+    }
+
+    /// Shows a message, e.g. the last move, a hint or how to enter a move.
+    fn show_message(&mut self, message: &str);
+
+    /// Shows why the last move or command was rejected.
+    fn show_error(&mut self, message: &str);
+
+    /// Updates the time the player has `left` while they are typing their move.
+    ///
+    /// It is called every second, and with zero once the time ran out. Defaults to doing nothing,
+    /// since the time left is also shown as a message before every prompt.
+    fn update_countdown(&mut self, left: Duration) {
+        let _ = left;
+    }
+
+    /// Announces that it is the player's turn.
+    fn announce_turn(&mut self, player: &Player);
+
+    /// Announces that it is the player's turn to make move `move_number`, counted from 1, in
+    /// `round` of a match if the game is part of one.
+    ///
+    /// Defaults to `announce_turn`, for outputs that don't number moves.
+    fn announce_move(&mut self, player: &Player, move_number: usize, round: Option<usize>) {
+        let _ = (move_number, round);
+        self.announce_turn(player);
+    }
+
+    /// Announces that the game ended with `status`.
+    ///
+    /// `message` describes the result for the players, e.g. the winner and a summary of the game.
+    fn announce_result(&mut self, status: &GameStatus, message: &str);
+}
+
+impl Default for Box<dyn Output> {
+    fn default() -> Self {
+        Box::new(StdoutOutput::default())
+    }
+}
+
+/// Prints the game to stdout, clearing the terminal between turns.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutOutput {
+    /// Whether the winner is announced in a box drawn around the result.
+    pub banner: bool,
+}
+
+impl Output for StdoutOutput {
+    fn clear(&mut self) {
+        util::clear_terminal();
+    }
+
+    fn render_board(&mut self, board: &Board, display: &DisplaySettings) {
+        println!("{}", board.display_with(display));
+    }
+
+    fn show_message(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    fn show_error(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    /// Redraws the time left on the line above the prompt, without disturbing what the player is
+    /// typing.
+    fn update_countdown(&mut self, left: Duration) {
+        let mut stdout = io::stdout();
+        if !stdout.is_terminal() {
+            return;
+        }
+
+        // Leave the line the player was typing on
+        if left.is_zero() {
+            println!();
+            return;
+        }
+
+        let _ = crossterm::execute!(
+            stdout,
+            SavePosition,
+            MoveToPreviousLine(2),
+            Clear(ClearType::CurrentLine),
+        );
+        print!("Time left: {}s", left.as_secs_f64().ceil());
+        let _ = crossterm::execute!(stdout, RestorePosition);
+        let _ = stdout.flush();
+    }
+
+    fn announce_turn(&mut self, player: &Player) {
+        println!("{}'s ({}) Turn", player.name, player.token);
+    }
+
+    fn announce_move(&mut self, player: &Player, move_number: usize, round: Option<usize>) {
+        println!("{}", describe_turn(player, move_number, round));
+    }
+
+    fn announce_result(&mut self, status: &GameStatus, message: &str) {
+        print_result(status, message, self.banner);
+    }
+}
+
+/// Prints the game to stdout without clearing the terminal, like a log.
+///
+/// The board is only printed after it changed, so rejected moves, hints and undone turns don't
+/// repeat it. Animations and the running countdown are left out. Everything else is printed as
+/// by `StdoutOutput`.
+#[derive(Clone, Debug, Default)]
+pub struct QuietOutput {
+    /// The board printed last, in compact form.
+    last_board: Option<String>,
+    /// Whether the winner is announced in a box drawn around the result.
+    pub banner: bool,
+}
+
+impl Output for QuietOutput {
+    fn clear(&mut self) {}
+
+    fn render_board(&mut self, board: &Board, display: &DisplaySettings) {
+        let compact = board.to_compact();
+        if self.last_board.as_ref() != Some(&compact) {
+            println!("{}", board.display_with(display));
+            self.last_board = Some(compact);
+        }
+    }
+
+    fn render_frame(&mut self, _board: &Board, _display: &DisplaySettings) {}
+
+    fn show_message(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    fn show_error(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    fn announce_turn(&mut self, player: &Player) {
+        println!("{}'s ({}) Turn", player.name, player.token);
+    }
+
+    fn announce_move(&mut self, player: &Player, move_number: usize, round: Option<usize>) {
+        println!("{}", describe_turn(player, move_number, round));
+    }
+
+    fn announce_result(&mut self, status: &GameStatus, message: &str) {
+        print_result(status, message, self.banner);
+    }
+}
+
+/// Describes whose turn it is and how far into the game, e.g. "Move 12 — Alice's (a) Turn".
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::output::describe_turn;
+/// # use connect_four::Player;
+/// let alice = Player::with_token("Alice", 'a');
+///
+/// assert_eq!(describe_turn(&alice, 12, None), "Move 12 — Alice's (a) Turn");
+/// assert_eq!(describe_turn(&alice, 1, Some(2)), "Round 2, Move 1 — Alice's (a) Turn");
+/// ```
+pub fn describe_turn(player: &Player, move_number: usize, round: Option<usize>) -> String {
+    let turn = format!(
+        "Move {} — {}'s ({}) Turn",
+        move_number, player.name, player.token
+    );
+
+    match round {
+        Some(round) => format!("Round {}, {}", round, turn),
+        None => turn,
+    }
+}
+
+/// Prints the result of a game.
+///
+/// With `banner` set the first line of `message`, which names the winner, is drawn in a box.
+/// Draws are printed as they are.
+fn print_result(status: &GameStatus, message: &str, banner: bool) {
+    if !banner || !matches!(status, GameStatus::Win(_)) {
+        println!("{}", message);
+        return;
+    }
+
+    let (result, rest) = message.split_once('\n').unwrap_or((message, ""));
+    println!("{}", render::banner(result));
+    if !rest.is_empty() {
+        println!("{}", rest);
+    }
+}
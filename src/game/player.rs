@@ -2,49 +2,279 @@
 //!
 //! Each player has a unique name and token. The token is a single character that represents the player on the game board.
 //!
+//! Players created with `Player::new` are assigned a token when the game is created, starting with `a` for the first player.
+//!
+//! Players are displayed as their name followed by their token, e.g. `Alice (a)`, and can be
+//! parsed from the same format.
+//!
 //! ## Example
 //!
 //! ```
-//! use connect_four::Player;
+//! use connect_four::{Game, Player};
 //!
-//! let player = Player::new("Alice");
-//! println!("Player name: {}", player.name);
-//! println!("Player token: {}", player.token);
+//! let game = Game::new(6, 7, 4, vec![Player::new("Alice"), Player::new("Bob")]).unwrap();
+//! for player in &game.players {
+//!     println!("Player name: {}", player.name);
+//!     println!("Player token: {}", player.token);
+//! }
 //! ```
 
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use colored::Color;
+use std::fmt;
+use std::str::FromStr;
+use unicode_width::UnicodeWidthStr;
+
+/// Placeholder token of players that are assigned a token by the game.
+pub const AUTO_TOKEN: &str = "";
+
+/// The widest token allowed, in terminal columns. Emoji take up two columns.
+pub const MAX_TOKEN_WIDTH: usize = 2;
+
+/// The tokens assigned to players without one, in order of their position in the game.
+pub(crate) const AUTO_TOKENS: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
 
-/// The next token to be assigned to a player.
+/// The colors assigned to players, in order of their position in the game.
 ///
-/// This is a thread-safe static variable that is shared across all players.
-static NEXT_TOKEN: Lazy<Mutex<char>> = Lazy::new(|| Mutex::new('a'));
+/// The palette is cycled when there are more players than colors.
+pub(crate) const PALETTE: [Color; 12] = [
+    Color::Red,
+    Color::Yellow,
+    Color::Blue,
+    Color::Green,
+    Color::Magenta,
+    Color::Cyan,
+    Color::BrightRed,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightGreen,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+];
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Represents a player in the game.
 pub struct Player {
     /// This `name` field represents the name of the player.
     pub name: String,
     /// This `token` field represents the token of the player that is used on the game board.
-    pub token: char,
+    ///
+    /// Usually a single letter, but any short symbol works, including emoji made of several
+    /// code points such as `🔴`.
+    pub token: String,
+    /// This `color` field represents the color the token is displayed in.
+    ///
+    /// Players without a chosen color are assigned one from the palette when the game is
+    /// created. Chosen colors must differ between players.
+    #[cfg_attr(feature = "serde", serde(default, with = "color_serde"))]
+    pub color: Option<Color>,
+    /// This `team` field represents the team the player wins together with, if any.
+    ///
+    /// Lines may mix the tokens of teammates. Players without a team only win on their own.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub team: Option<usize>,
 }
 
 impl Player {
-    /// Creates a new player with the given name and generates a token.
+    /// Creates a new player with the given name.
+    ///
+    /// The token and color are assigned from the player's position when the game is created.
     pub fn new<S: Into<String>>(name: S) -> Self {
+        Self::with_token(name, AUTO_TOKEN)
+    }
+
+    /// Creates a new player with the given name and token.
+    ///
+    /// The color is assigned from the player's position when the game is created.
+    pub fn with_token<S: Into<String>, T: Into<String>>(name: S, token: T) -> Self {
         let name = name.into();
         if name.is_empty() {
             panic!("Player must have a name.")
         }
 
-        let mut token_lock = NEXT_TOKEN.lock().unwrap();
-        let token = *token_lock;
+        Self {
+            name,
+            token: token.into(),
+            color: None,
+            team: None,
+        }
+    }
+
+    /// Chooses the color the player's token is displayed in, instead of one from the palette.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use colored::Color;
+    /// # use connect_four::{Game, Player};
+    /// let players = vec![
+    ///     Player::new("Alice").with_color(Color::Blue),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let game = Game::new(6, 7, 4, players).unwrap();
+    ///
+    /// // Bob gets the first color of the palette
+    /// assert_eq!(game.players[0].color, Some(Color::Blue));
+    /// assert_eq!(game.players[1].color, Some(Color::Red));
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice").with_color(Color::Blue),
+    ///     Player::new("Bob").with_color(Color::Blue),
+    /// ];
+    /// assert!(Game::new(6, 7, 4, players).is_err());
+    /// ```
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Returns whether the player wins together with `other`.
+    ///
+    /// This holds for the player themselves and for players on the same team.
+    pub fn is_teammate(&self, other: &Player) -> bool {
+        self == other || (self.team.is_some() && self.team == other.team)
+    }
+
+    /// Returns how many terminal columns the player's token takes up.
+    pub fn token_width(&self) -> usize {
+        UnicodeWidthStr::width(self.token.as_str())
+    }
+}
+
+impl fmt::Display for Player {
+    /// Formats the player as their name followed by their token, e.g. `Alice (a)`.
+    ///
+    /// Players still waiting for a token are shown by name only.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token == AUTO_TOKEN {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} ({})", self.name, self.token)
+        }
+    }
+}
+
+/// Represents a player that could not be parsed.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParsePlayerError {
+    /// The name is missing, e.g. in `(a)`.
+    MissingName(String),
+    /// The parentheses hold no token, e.g. in `Alice ()`.
+    MissingToken(String),
+}
 
-        // Increment token for the next player
-        if *token_lock < 'z' {
-            *token_lock = (token as u8 + 1) as char;
+impl fmt::Display for ParsePlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePlayerError::MissingName(input) => {
+                write!(f, "Player {:?} has no name.", input)
+            }
+            ParsePlayerError::MissingToken(input) => {
+                write!(f, "Player {:?} has no token.", input)
+            }
         }
+    }
+}
+
+impl std::error::Error for ParsePlayerError {}
+
+impl FromStr for Player {
+    type Err = ParsePlayerError;
+
+    /// Parses a player as displayed, e.g. `Alice (a)` or `Alice(a)`.
+    ///
+    /// A name without a token in parentheses, e.g. `Alice`, is assigned a token when the game is
+    /// created. Tokens are not checked against other players here, that is up to the game.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::Player;
+    /// let player: Player = "Alice (🔴)".parse().unwrap();
+    /// assert_eq!(player.name, "Alice");
+    /// assert_eq!(player.token, "🔴");
+    /// assert_eq!(player.to_string(), "Alice (🔴)");
+    ///
+    /// assert_eq!("Bob(b)".parse::<Player>(), Ok(Player::with_token("Bob", "b")));
+    /// assert_eq!("Carol".parse::<Player>(), Ok(Player::new("Carol")));
+    /// assert!("(c)".parse::<Player>().is_err());
+    /// assert!("Carol ()".parse::<Player>().is_err());
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// - If the name is missing.
+    /// - If the parentheses hold no token.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        let (name, token) = match input
+            .strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once('('))
+        {
+            Some((name, token)) => {
+                let token = token.trim();
+                if token.is_empty() {
+                    return Err(ParsePlayerError::MissingToken(input.to_string()));
+                }
+                (name.trim(), token)
+            }
+            None => (input, AUTO_TOKEN),
+        };
+
+        if name.is_empty() {
+            return Err(ParsePlayerError::MissingName(input.to_string()));
+        }
+
+        Ok(Self::with_token(name, token))
+    }
+}
+
+/// Serializes colors by name, since `colored` does not support serde.
+#[cfg(feature = "serde")]
+mod color_serde {
+    use colored::Color;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let Some(color) = color else {
+            return serializer.serialize_none();
+        };
+
+        let name = match color {
+            Color::Black => "black".to_string(),
+            Color::Red => "red".to_string(),
+            Color::Green => "green".to_string(),
+            Color::Yellow => "yellow".to_string(),
+            Color::Blue => "blue".to_string(),
+            Color::Magenta => "magenta".to_string(),
+            Color::Cyan => "cyan".to_string(),
+            Color::White => "white".to_string(),
+            Color::BrightBlack => "bright black".to_string(),
+            Color::BrightRed => "bright red".to_string(),
+            Color::BrightGreen => "bright green".to_string(),
+            Color::BrightYellow => "bright yellow".to_string(),
+            Color::BrightBlue => "bright blue".to_string(),
+            Color::BrightMagenta => "bright magenta".to_string(),
+            Color::BrightCyan => "bright cyan".to_string(),
+            Color::BrightWhite => "bright white".to_string(),
+            Color::AnsiColor(_) => "white".to_string(),
+            Color::TrueColor { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        };
+        serializer.serialize_str(&name)
+    }
 
-        Self { name, token }
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        let Some(name) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        name.parse()
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("unknown color: {}", name)))
     }
 }
@@ -0,0 +1,189 @@
+//! Protocol module
+//!
+//! Contains a line based JSON protocol, so a game can be driven by another program through
+//! stdin and stdout instead of by a person at the terminal.
+//!
+//! Moves are read one JSON object per line, e.g. `{"col":3}`, with columns counted from 0.
+//! Everything the game shows is written one JSON object per line, tagged with a `type` field:
+//!
+//! ```text
+//! {"type":"board","board":{...}}
+//! {"type":"turn","player":{"name":"Alice","token":"a",...}}
+//! {"type":"error","message":"Column 3 is full."}
+//! {"type":"result","status":{"Win":{...}},"message":"The winner is: Alice (a)\n..."}
+//! ```
+//!
+//! This module is only available with the `serde` feature.
+
+use super::board::Board;
+use super::input::StdinInput;
+use super::output::Output;
+use super::render::DisplaySettings;
+use super::strategy::{Command, Console, Strategy};
+use super::{GameStatus, Player};
+use std::io::{self, Write};
+
+/// Represents how a game talks to its players.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+pub enum Protocol {
+    /// Prompts and boards for a person at the terminal.
+    #[default]
+    Human,
+    /// JSON lines for another program.
+    Jsonl,
+}
+
+/// Represents a line written by the game.
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProtocolMessage<'a> {
+    /// The board at the start of a turn.
+    Board {
+        /// The game board.
+        board: &'a Board,
+    },
+    /// It is the player's turn.
+    Turn {
+        /// The player to move.
+        player: &'a Player,
+    },
+    /// Information for the players, e.g. the last move or a hint.
+    Message {
+        /// The text of the message.
+        message: &'a str,
+    },
+    /// The last move or line was rejected.
+    Error {
+        /// Why it was rejected.
+        message: &'a str,
+    },
+    /// The game ended.
+    Result {
+        /// The final status of the game.
+        status: &'a GameStatus,
+        /// The result described for people, including a summary of the game.
+        message: &'a str,
+    },
+}
+
+impl ProtocolMessage<'_> {
+    /// Writes the message to stdout as a single line.
+    pub fn send(&self) {
+        let json = serde_json::to_string(self).expect("messages are always serializable");
+        let mut stdout = io::stdout().lock();
+        // A reader that went away will notice the game ending by itself
+        let _ = writeln!(stdout, "{}", json);
+        let _ = stdout.flush();
+    }
+}
+
+/// Represents a line read from the program playing a move.
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct MoveMessage {
+    /// The column to drop the token into, counted from 0.
+    pub col: usize,
+}
+
+/// Writes the game as JSON lines to stdout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonlOutput;
+
+impl Output for JsonlOutput {
+    /// Nothing needs to be cleared, since every state is a line of its own.
+    fn clear(&mut self) {}
+
+    /// Boards are sent as data, so the display settings don't apply.
+    fn render_board(&mut self, board: &Board, _display: &DisplaySettings) {
+        ProtocolMessage::Board { board }.send();
+    }
+
+    /// Only boards that were played are sent, not the frames of animations.
+    fn render_frame(&mut self, _board: &Board, _display: &DisplaySettings) {}
+
+    fn show_message(&mut self, message: &str) {
+        ProtocolMessage::Message { message }.send();
+    }
+
+    fn show_error(&mut self, message: &str) {
+        ProtocolMessage::Error { message }.send();
+    }
+
+    fn announce_turn(&mut self, player: &Player) {
+        ProtocolMessage::Turn { player }.send();
+    }
+
+    fn announce_result(&mut self, status: &GameStatus, message: &str) {
+        ProtocolMessage::Result { status, message }.send();
+    }
+}
+
+/// A strategy that reads moves as JSON lines, e.g. `{"col":3}`.
+///
+/// Lines that aren't valid moves are answered with an error message and skipped. The game ends
+/// once input is closed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonlStrategy;
+
+impl Strategy for JsonlStrategy {
+    fn choose_column(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize {
+        match self.choose_command(board, me, opponents, tokens_to_win) {
+            Command::Drop(col) => col,
+            // There is nothing left to read, so any column will do
+            _ => board.open_columns().first().copied().unwrap_or(0),
+        }
+    }
+
+    fn choose_command(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        let mut console = Console {
+            input: &mut StdinInput::default(),
+            output: &mut JsonlOutput,
+            display: &DisplaySettings::default(),
+        };
+        self.choose_command_from(&mut console, board, me, opponents, tokens_to_win)
+    }
+
+    fn choose_command_from(
+        &self,
+        console: &mut Console,
+        _board: &Board,
+        _me: &Player,
+        _opponents: &[Player],
+        _tokens_to_win: usize,
+    ) -> Command {
+        loop {
+            let line = match console.input.read_line() {
+                // Input was closed, so no more moves will come
+                Ok(line) if line.is_empty() => return Command::Quit,
+                Ok(line) => line,
+                Err(_) => return Command::Quit,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<MoveMessage>(&line) {
+                Ok(message) => return Command::Drop(message.col),
+                Err(err) => ProtocolMessage::Error {
+                    message: &format!("Invalid message: {}", err),
+                }
+                .send(),
+            }
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,150 @@
+//! Records module
+//!
+//! Contains the `Records` struct, which keeps the win, loss and draw tallies of every player
+//! across sessions in a small JSON file.
+//!
+//! Players are identified by name, so the same name always adds to the same record. A missing
+//! or unreadable file is treated as an empty leaderboard.
+
+use super::{GameStatus, Player};
+use std::collections::BTreeMap;
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file name of the records in the config directory.
+pub const RECORDS_FILE: &str = "stats.json";
+
+/// Represents the results of a single player.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub struct Record {
+    /// The number of games won.
+    pub wins: usize,
+    /// The number of games lost.
+    pub losses: usize,
+    /// The number of games drawn.
+    pub draws: usize,
+}
+
+impl Record {
+    /// Returns the number of games played.
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+}
+
+/// Represents the records of every player, keyed by name.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Records {
+    /// The record of each player.
+    pub players: BTreeMap<String, Record>,
+}
+
+impl Records {
+    /// Reads the records from `path`.
+    ///
+    /// Starts fresh if the file does not exist, and with a warning if it can't be read.
+    pub fn load(path: &Path) -> Self {
+        let input = match std::fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                eprintln!(
+                    "Warning: starting fresh records, {}: {}",
+                    path.display(),
+                    err
+                );
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&input).unwrap_or_else(|err| {
+            eprintln!(
+                "Warning: starting fresh records, {}: {}",
+                path.display(),
+                err
+            );
+            Self::default()
+        })
+    }
+
+    /// Writes the records to `path`, creating its directory if needed.
+    ///
+    /// ## Errors
+    ///
+    /// - If the directory or file can't be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let json = serde_json::to_string_pretty(self).expect("records are always serializable");
+        std::fs::write(path, json)
+    }
+
+    /// Adds the result of a finished game to the record of every player.
+    ///
+    /// A team win counts as a win for every member of the team. Unfinished games are ignored.
+    pub fn record(&mut self, players: &[Player], status: &GameStatus) {
+        for player in players {
+            let record = self.players.entry(player.name.clone()).or_default();
+
+            match status {
+                GameStatus::Ongoing => (),
+                GameStatus::Draw => record.draws += 1,
+                GameStatus::Win(winner) if player.is_teammate(winner) => record.wins += 1,
+                GameStatus::Win(_) => record.losses += 1,
+            }
+        }
+    }
+
+    /// Renders the standings, with the most wins first.
+    pub fn standings(&self) -> String {
+        if self.players.is_empty() {
+            return "No games recorded yet.".to_string();
+        }
+
+        let mut players: Vec<(&String, &Record)> = self.players.iter().collect();
+        players.sort_by(|(_, a), (_, b)| b.wins.cmp(&a.wins).then(a.losses.cmp(&b.losses)));
+
+        let width = players
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        let mut output = format!(
+            "{:width$}  Games  Wins  Losses  Draws",
+            "Player",
+            width = width.max(6)
+        );
+        for (name, record) in players {
+            output.push_str(&format!(
+                "\n{:width$}  {:>5}  {:>4}  {:>6}  {:>5}",
+                name,
+                record.games(),
+                record.wins,
+                record.losses,
+                record.draws,
+                width = width.max(6)
+            ));
+        }
+
+        output
+    }
+}
+
+/// Returns where records are kept when no path is given.
+///
+/// This is `connect_four/stats.json` in the user's config directory: `$XDG_CONFIG_HOME`,
+/// `~/.config` or `%APPDATA%`. Falls back to the current directory if none is set.
+pub fn default_path() -> PathBuf {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from));
+
+    match config_dir {
+        Some(dir) => dir.join("connect_four").join(RECORDS_FILE),
+        None => PathBuf::from(RECORDS_FILE),
+    }
+}
@@ -0,0 +1,400 @@
+//! Render module
+//!
+//! Contains the `BoardRenderer` trait, which turns a board into text, and the rendering styles
+//! players can pick from.
+//!
+//! `Board::display_with` and `Board::column_marker` render in the style of the
+//! `DisplaySettings` they are given, so new styles only need a renderer here.
+
+use super::board::{Board, BLOCKED_CELL};
+use super::player::AUTO_TOKENS;
+use super::util::Labels;
+use super::Player;
+use colored::{ColoredString, Colorize};
+use unicode_width::UnicodeWidthStr;
+
+/// Renders a board as text.
+pub trait BoardRenderer {
+    /// Renders the board, with the column indices above it.
+    fn render(&self, board: &Board, settings: &DisplaySettings) -> String;
+
+    /// Renders a line with a `v` marker above the selected column.
+    ///
+    /// Lines up with the output of `render` when printed directly above it.
+    fn column_marker(&self, board: &Board, selected: usize, settings: &DisplaySettings) -> String;
+}
+
+/// Describes how boards, lanes and cells are shown to the players.
+///
+/// A game keeps its settings in `Game::display` and hands them to its output and strategies, so
+/// games with different settings can run side by side.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::board::Board;
+/// # use connect_four::game::render::DisplaySettings;
+/// let roomy = DisplaySettings {
+///     cell_width: Some(3),
+///     ..DisplaySettings::default()
+/// };
+///
+/// assert_eq!(Board::new(1, 2).display_with(&roomy), "  0    1  \n[   ][   ]\n");
+/// assert_eq!(Board::new(1, 2).display(), "0  1  \n[ ][ ]\n");
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct DisplaySettings {
+    /// The style boards are rendered in.
+    pub style: Style,
+    /// The width inside every cell, so narrow boards can be drawn with roomier cells.
+    ///
+    /// Cells never get narrower than their tokens and column labels need. With None the width
+    /// is derived from the board alone.
+    pub cell_width: Option<usize>,
+    /// How lanes are labelled.
+    pub labels: Labels,
+}
+
+/// Represents the styles a board can be rendered in.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+pub enum Style {
+    /// Cells in brackets, e.g. `[a][ ]`.
+    #[default]
+    Ascii,
+    /// A grid of box-drawing characters with round discs.
+    Unicode,
+}
+
+impl Style {
+    /// Returns the renderer for the style.
+    pub fn renderer(self) -> &'static dyn BoardRenderer {
+        match self {
+            Style::Ascii => &AsciiRenderer,
+            Style::Unicode => &UnicodeRenderer,
+        }
+    }
+}
+
+/// Draws `text` inside a box of double lines, sized to its widest line.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::render;
+/// assert_eq!(
+///     render::banner("The winner is: Alice (a)"),
+///     "╔══════════════════════════╗\n\
+///      ║ The winner is: Alice (a) ║\n\
+///      ╚══════════════════════════╝"
+/// );
+/// ```
+pub fn banner(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let width = lines
+        .iter()
+        .map(|line| UnicodeWidthStr::width(*line))
+        .max()
+        .unwrap_or(0);
+
+    let mut output = format!("╔{}╗\n", "═".repeat(width + 2));
+    for line in lines {
+        // Pad by display width, since emoji take up two columns
+        let padding = " ".repeat(width - UnicodeWidthStr::width(line));
+        output.push_str(&format!("║ {}{} ║\n", line, padding));
+    }
+    output.push_str(&format!("╚{}╝", "═".repeat(width + 2)));
+    output
+}
+
+/// Colors `text` in the player's color, if they have one.
+fn paint(text: &str, player: &Player) -> ColoredString {
+    match player.color {
+        Some(color) => text.color(color),
+        None => text.normal(),
+    }
+}
+
+/// Dims `text` if it is a previewed token, see `Board::preview`.
+fn faint(text: ColoredString, ghost: bool) -> ColoredString {
+    if ghost {
+        text.dimmed()
+    } else {
+        text
+    }
+}
+
+/// Returns the width of the labels in front of the rows, which are only shown with sideways
+/// gravity.
+fn row_label_width(board: &Board) -> usize {
+    if board.is_sideways() {
+        board.rows.len().to_string().len() + 1
+    } else {
+        0
+    }
+}
+
+/// Returns the width of the widest token on the board, in terminal columns.
+///
+/// Every cell is as wide as the widest token, so columns stay aligned when emoji are used.
+fn token_width(board: &Board) -> usize {
+    board
+        .iter_cells()
+        .filter_map(|(_, _, cell)| cell.as_ref())
+        .map(Player::token_width)
+        .max()
+        .unwrap_or(1)
+}
+
+/// Renders cells in brackets, e.g. `[a][ ]`.
+pub struct AsciiRenderer;
+
+impl AsciiRenderer {
+    /// Returns the width of a column, excluding the separator.
+    fn max_col_width(board: &Board, settings: &DisplaySettings) -> usize {
+        // Determine the width needed to display the largest column index or token
+        let label_width = if settings.labels.alpha {
+            1
+        } else {
+            board.rows[0].len().to_string().len()
+        };
+        let width = label_width.max(token_width(board));
+        width.max(settings.cell_width.unwrap_or(0)) + 1
+    }
+
+    /// Formats `text` of display width `width` to fill the inside of a cell, centered.
+    fn center(text: &str, width: usize, max_col_width: usize) -> String {
+        let left = (max_col_width - 1 - width) / 2;
+        let right = max_col_width - 1 - width - left;
+        format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+    }
+}
+
+impl BoardRenderer for AsciiRenderer {
+    /// With sideways gravity each row is prefixed with its index so players can pick a lane.
+    fn render(&self, board: &Board, settings: &DisplaySettings) -> String {
+        let mut output = String::new();
+
+        let max_col_width = Self::max_col_width(board, settings);
+        let row_label_width = row_label_width(board);
+
+        output.push_str(&" ".repeat(row_label_width));
+
+        // Print the column headers (indices)
+        for col in 0..board.rows[0].len() {
+            let label = settings.labels.lane(col);
+            // Wider cells center their index over the token, otherwise it starts above the cell
+            if settings.cell_width.is_some() {
+                output.push_str(&format!(
+                    " {} ",
+                    Self::center(&label, label.len(), max_col_width)
+                ));
+            } else {
+                output.push_str(&format!("{:width$} ", label, width = max_col_width));
+            }
+        }
+
+        output.push('\n');
+
+        // Print the board rows
+        for (i, j, cell) in board.iter_cells() {
+            if j == 0 {
+                if i > 0 {
+                    output.push('\n');
+                }
+
+                if board.is_sideways() {
+                    output.push_str(&format!(
+                        "{:<width$}",
+                        settings.labels.lane(i),
+                        width = row_label_width
+                    ));
+                }
+            }
+
+            // Only the token is colored so the brackets and empty cells stay neutral.
+            // Pad by display width, since emoji take up two columns.
+            let ghost = board.ghost == Some((i, j));
+            let symbol = match cell {
+                Some(player) => Self::center(
+                    &faint(paint(&player.token, player), ghost).to_string(),
+                    player.token_width(),
+                    max_col_width,
+                ),
+                None if board.is_blocked(i, j) => {
+                    Self::center(BLOCKED_CELL, BLOCKED_CELL.len(), max_col_width)
+                }
+                None => " ".repeat(max_col_width - 1),
+            };
+            // The token placed last is put in parentheses instead, a previewed one in braces
+            if cell.is_some() && ghost {
+                output.push_str(&format!("{{{}}}", symbol));
+            } else if cell.is_some() && board.last_placed == Some((i, j)) {
+                output.push_str(&format!("({})", symbol));
+            } else {
+                output.push_str(&format!("[{}]", symbol));
+            }
+        }
+
+        output.push('\n');
+        output
+    }
+
+    fn column_marker(&self, board: &Board, selected: usize, settings: &DisplaySettings) -> String {
+        let max_col_width = Self::max_col_width(board, settings);
+
+        (0..board.rows[0].len())
+            .map(|col| {
+                let marker = if col == selected { "v" } else { " " };
+                if settings.cell_width.is_some() {
+                    format!(" {} ", Self::center(marker, 1, max_col_width))
+                } else {
+                    format!("{:>width$} ", marker, width = max_col_width)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Renders a grid of box-drawing characters with a disc in every occupied cell.
+///
+/// The automatically assigned tokens `a`, `b`, `c`, ... are drawn as `●`, `○`, `◆`, ... so
+/// players can be told apart without colors. Other tokens are drawn as they are.
+pub struct UnicodeRenderer;
+
+impl UnicodeRenderer {
+    /// The discs drawn for the first automatically assigned tokens.
+    const DISCS: [char; 8] = ['●', '○', '◆', '◇', '■', '□', '▲', '△'];
+
+    /// The shading drawn across blocked cells.
+    const BLOCK: &'static str = "░";
+
+    /// Returns the width inside a cell, excluding the borders.
+    ///
+    /// The width is odd so single-column discs sit exactly in the middle.
+    fn cell_width(board: &Board, settings: &DisplaySettings) -> usize {
+        let label_width = settings.labels.lane(board.rows[0].len() - 1).len();
+        let width = (label_width.max(token_width(board)) + 2) | 1;
+        width.max(settings.cell_width.unwrap_or(0))
+    }
+
+    /// Returns the disc drawn for the player's token, and its display width.
+    fn disc(player: &Player) -> (String, usize) {
+        AUTO_TOKENS
+            .chars()
+            .position(|token| player.token == token.to_string())
+            .and_then(|index| Self::DISCS.get(index))
+            .map(|disc| (disc.to_string(), 1))
+            .unwrap_or_else(|| (player.token.clone(), player.token_width()))
+    }
+
+    /// Renders a horizontal border of the grid.
+    fn border(
+        board: &Board,
+        settings: &DisplaySettings,
+        left: char,
+        middle: char,
+        right: char,
+    ) -> String {
+        let segment = "─".repeat(Self::cell_width(board, settings));
+        let segments = vec![segment; board.rows[0].len()];
+
+        format!(
+            "{}{}{}{}\n",
+            " ".repeat(row_label_width(board)),
+            left,
+            segments.join(&middle.to_string()),
+            right
+        )
+    }
+}
+
+impl BoardRenderer for UnicodeRenderer {
+    fn render(&self, board: &Board, settings: &DisplaySettings) -> String {
+        let cell_width = Self::cell_width(board, settings);
+        let row_label_width = row_label_width(board);
+        let mut output = String::new();
+
+        // Center each column index over its cell
+        output.push_str(&" ".repeat(row_label_width + 1));
+        for col in 0..board.rows[0].len() {
+            output.push_str(&format!(
+                "{:^width$} ",
+                settings.labels.lane(col),
+                width = cell_width
+            ));
+        }
+        output.push('\n');
+
+        output.push_str(&Self::border(board, settings, '┌', '┬', '┐'));
+
+        for (i, j, cell) in board.iter_cells() {
+            if j == 0 {
+                if i > 0 {
+                    output.push('\n');
+                    output.push_str(&Self::border(board, settings, '├', '┼', '┤'));
+                }
+
+                if board.is_sideways() {
+                    output.push_str(&format!(
+                        "{:<width$}",
+                        settings.labels.lane(i),
+                        width = row_label_width
+                    ));
+                }
+
+                output.push('│');
+            }
+
+            // Blocked cells are shaded in completely
+            if board.is_blocked(i, j) {
+                output.push_str(&format!("{}│", Self::BLOCK.repeat(cell_width)));
+                continue;
+            }
+
+            // Pad by hand, since colored discs contain invisible escape codes
+            let ghost = board.ghost == Some((i, j));
+            let (disc, width) = match cell {
+                Some(player) => {
+                    let (disc, width) = Self::disc(player);
+                    (faint(paint(&disc, player), ghost).to_string(), width)
+                }
+                None => (" ".to_string(), 1),
+            };
+            let left = (cell_width - width) / 2;
+            let right = cell_width - width - left;
+            // The token placed last is put in parentheses, a previewed one in braces, which
+            // always fit around the disc
+            let (open, close) = if cell.is_some() && ghost {
+                ("{", "}")
+            } else if cell.is_some() && board.last_placed == Some((i, j)) {
+                ("(", ")")
+            } else {
+                (" ", " ")
+            };
+            output.push_str(&format!(
+                "{}{}{}{}{}│",
+                " ".repeat(left - 1),
+                open,
+                disc,
+                close,
+                " ".repeat(right - 1)
+            ));
+        }
+        output.push('\n');
+
+        output.push_str(&Self::border(board, settings, '└', '┴', '┘'));
+        output
+    }
+
+    fn column_marker(&self, board: &Board, selected: usize, settings: &DisplaySettings) -> String {
+        let cell_width = Self::cell_width(board, settings);
+        let mut output = " ".repeat(row_label_width(board) + 1);
+
+        for col in 0..board.rows[0].len() {
+            let marker = if col == selected { 'v' } else { ' ' };
+            output.push_str(&format!("{:^width$} ", marker, width = cell_width));
+        }
+
+        output
+    }
+}
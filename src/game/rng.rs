@@ -0,0 +1,57 @@
+//! Random number module
+//!
+//! Contains a small seeded random number generator, so that games involving chance can be
+//! reproduced from their seed.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A xorshift64* pseudo-random number generator.
+///
+/// Not suitable for cryptography, but fast and deterministic for a given seed.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    /// The current state. Never zero.
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a new generator from the given seed.
+    pub fn new(seed: u64) -> Self {
+        // Scramble the seed with splitmix64 so nearby seeds give unrelated sequences
+        let mut state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        state ^= state >> 31;
+
+        // xorshift gets stuck on a zero state
+        Self {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    /// Returns a seed derived from the current time, for when reproducibility isn't needed.
+    pub fn time_seed() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a random number in `0..bound`.
+    ///
+    /// ## Panics
+    ///
+    /// - If `bound` is zero.
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "bound must be greater than 0");
+        (self.next_u64() % bound as u64) as usize
+    }
+}
@@ -0,0 +1,183 @@
+//! Roster module
+//!
+//! Contains the `Roster` struct, which reads the players of a game from a plain text file, one
+//! player per line. This suits tournaments with fixed rosters better than listing every player
+//! on the command line.
+//!
+//! Each line holds a name, optionally followed by a token and a bot difficulty, separated by
+//! commas. Blank lines and lines starting with `#` are skipped:
+//!
+//! ```text
+//! # name, token, difficulty
+//! Alice, x
+//! Bob
+//! Computer, , medium
+//! ```
+
+use super::ai::Difficulty;
+use super::player::AUTO_TOKEN;
+use super::util::Args;
+use super::{Game, Player};
+use clap::ValueEnum;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Represents a player read from a roster.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RosterEntry {
+    /// The name of the player.
+    pub name: String,
+    /// The token of the player, assigned automatically if None.
+    pub token: Option<String>,
+    /// How strong the player is if the computer plays them, or None for a person.
+    pub difficulty: Option<Difficulty>,
+}
+
+/// Represents the players of a roster file, in order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Roster {
+    /// The players of the roster.
+    pub players: Vec<RosterEntry>,
+}
+
+impl Roster {
+    /// Reads a roster from the file at `path`.
+    ///
+    /// ## Errors
+    ///
+    /// - If the file can't be read.
+    /// - If the roster is invalid, see `parse`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let input =
+            std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Self::parse(&input).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Parses a roster, one player per line.
+    ///
+    /// ## Errors
+    ///
+    /// - If a line has no name, more than three fields or an unknown difficulty.
+    /// - If a name or token is used twice, or a token is not allowed by `Game::new`.
+    /// - If there are fewer than two players.
+    ///
+    /// Errors name the line they were found on.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::ai::Difficulty;
+    /// # use connect_four::game::roster::Roster;
+    /// let roster = Roster::parse("# Finals\nAlice, x\nComputer, , medium\n").unwrap();
+    /// assert_eq!(roster.players[0].token.as_deref(), Some("x"));
+    /// assert_eq!(roster.players[1].token, None);
+    /// assert_eq!(roster.players[1].difficulty, Some(Difficulty::Medium));
+    ///
+    /// assert_eq!(
+    ///     Roster::parse("Alice, x\nBob, x").unwrap_err(),
+    ///     "line 2: duplicate token \"x\""
+    /// );
+    /// assert_eq!(
+    ///     Roster::parse("Alice\nBob, b, genius").unwrap_err(),
+    ///     "line 2: unknown difficulty \"genius\", expected easy, medium or hard"
+    /// );
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut players = Vec::new();
+        let mut names = HashSet::new();
+        let mut tokens = HashSet::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", index + 1, message);
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() > 3 {
+                return Err(error(format!(
+                    "expected at most 3 fields, found {}",
+                    fields.len()
+                )));
+            }
+
+            let name = fields[0];
+            if name.is_empty() {
+                return Err(error("missing name".to_string()));
+            }
+            if !names.insert(name) {
+                return Err(error(format!("duplicate name {:?}", name)));
+            }
+
+            let token = fields
+                .get(1)
+                .filter(|token| !token.is_empty())
+                .map(|token| token.to_string());
+            if let Some(token) = &token {
+                // The same checks `Game::new` makes, so they can be pinned to the line
+                Game::validate_players(&[Player::with_token(name, token.as_str())])
+                    .map_err(|err| error(err.to_string()))?;
+                if !tokens.insert(token.clone()) {
+                    return Err(error(format!("duplicate token {:?}", token)));
+                }
+            }
+
+            let difficulty = match fields.get(2).filter(|difficulty| !difficulty.is_empty()) {
+                Some(difficulty) => Some(Difficulty::from_str(difficulty, true).map_err(|_| {
+                    error(format!(
+                        "unknown difficulty {:?}, expected easy, medium or hard",
+                        difficulty
+                    ))
+                })?),
+                None => None,
+            };
+
+            players.push(RosterEntry {
+                name: name.to_string(),
+                token,
+                difficulty,
+            });
+        }
+
+        if players.len() < 2 {
+            return Err(format!(
+                "expected at least 2 players, found {}",
+                players.len()
+            ));
+        }
+
+        Ok(Self { players })
+    }
+
+    /// Sets the players and tokens of `args` to those of the roster, and adds its computer
+    /// players to the bots.
+    pub fn apply(self, args: &mut Args) {
+        args.players = self
+            .players
+            .iter()
+            .map(|player| player.name.clone())
+            .collect();
+
+        args.tokens = if self.players.iter().any(|player| player.token.is_some()) {
+            self.players
+                .iter()
+                .map(|player| {
+                    player
+                        .token
+                        .clone()
+                        .unwrap_or_else(|| AUTO_TOKEN.to_string())
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for player in self.players {
+            if let Some(difficulty) = player.difficulty {
+                args.bots.push(player.name.clone());
+                args.bot_difficulties.insert(player.name, difficulty);
+            }
+        }
+    }
+}
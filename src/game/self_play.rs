@@ -0,0 +1,121 @@
+//! Self-play module
+//!
+//! Contains a harness that plays many random games to completion, to catch panics and logic
+//! errors in token placement, win detection and draw detection.
+//!
+//! Every game is played by seeded `RandomBot`s on a randomly chosen board, so a failing game can
+//! be reproduced from the seed it reports.
+
+use super::ai::RandomBot;
+use super::board::Gravity;
+use super::rng::Rng;
+use super::strategy::Strategy;
+use super::{Game, GameStatus, Player};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Plays `games` random games, starting from `seed`.
+///
+/// Each game must end in a win or a draw within one move per cell, and its result must agree
+/// with a scan of the whole board.
+///
+/// ## Errors
+///
+/// - If any game panics, rejects a move, runs too long or ends with the wrong result.
+///   Every failure is listed with the seed of its game.
+pub fn self_play(games: usize, seed: u64) -> Result<(), String> {
+    let failures: Vec<String> = (0..games as u64)
+        .filter_map(|index| {
+            let game_seed = seed.wrapping_add(index);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| play_random_game(game_seed)));
+
+            match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(format!("Game {} (seed {}): {}", index, game_seed, err)),
+                Err(_) => Some(format!("Game {} (seed {}): panicked", index, game_seed)),
+            }
+        })
+        .collect();
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+/// Plays one random game on a board picked from `seed`.
+fn play_random_game(seed: u64) -> Result<(), String> {
+    let mut rng = Rng::new(seed);
+    let mut game = random_game(&mut rng);
+
+    let bots: Vec<RandomBot> = (0..game.players.len())
+        .map(|index| RandomBot::new(seed.wrapping_add(index as u64)))
+        .collect();
+    let cells = game.board.rows.len() * game.board.rows[0].len();
+
+    for _ in 0..=cells {
+        match game.status() {
+            GameStatus::Ongoing => (),
+            GameStatus::Win(winner) => {
+                return match Game::find_winner_on(
+                    &game.board,
+                    game.tokens_to_win,
+                    game.exact_length,
+                    game.win_directions,
+                ) {
+                    Some(line) if line.player == winner => Ok(()),
+                    _ => Err(format!("{} won without a line", winner.name)),
+                };
+            }
+            GameStatus::Draw => {
+                if Game::find_winner_on(
+                    &game.board,
+                    game.tokens_to_win,
+                    game.exact_length,
+                    game.win_directions,
+                )
+                .is_some()
+                {
+                    return Err("a draw was reported on a won board".to_string());
+                }
+                return Ok(());
+            }
+        }
+
+        let turn = game.current_turn();
+        let count = game.players.len();
+        let me = game.players[turn].clone();
+        let opponents: Vec<Player> = (1..count)
+            .map(|offset| game.players[(turn + offset) % count].clone())
+            .collect();
+        let col = bots[turn].choose_column(&game.board, &me, &opponents, game.tokens_to_win);
+
+        game.play_move(col)
+            .map_err(|err| format!("column {} was rejected: {}", col, err))?;
+    }
+
+    Err(format!("the game did not end after {} moves", cells))
+}
+
+/// Creates a game with a random board size, tokens to win, player count and gravity.
+fn random_game(rng: &mut Rng) -> Game {
+    loop {
+        let rows = 1 + rng.next_below(10);
+        let cols = 1 + rng.next_below(10);
+        let tokens_to_win = 2 + rng.next_below(5);
+        let players = (0..2 + rng.next_below(3))
+            .map(|index| Player::new(format!("Bot {}", index + 1)))
+            .collect();
+
+        // Configurations the game rejects are simply picked again
+        if let Ok(mut game) = Game::new(rows, cols, tokens_to_win, players) {
+            game.board.gravity = match rng.next_below(4) {
+                0 => Gravity::Down,
+                1 => Gravity::Up,
+                2 => Gravity::Left,
+                _ => Gravity::Right,
+            };
+            return game;
+        }
+    }
+}
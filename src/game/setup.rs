@@ -0,0 +1,119 @@
+//! Setup module
+//!
+//! Contains the interactive prompts used to set up a game when no players are given on the
+//! command line.
+//!
+//! Every prompt shows its default in brackets, which is used when the answer is left empty.
+//! Invalid answers are explained and asked again.
+
+use super::input::{InputSource, StdinInput};
+use super::player::AUTO_TOKENS;
+use super::util::Args;
+use super::{Game, Player};
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// Asks for the players, board size and tokens to win, and stores the answers in `args`.
+///
+/// The current values of `args` are offered as defaults for the board settings.
+///
+/// ## Errors
+///
+/// - If input is closed or can't be read before the setup is complete.
+pub fn prompt_setup(args: &mut Args) -> io::Result<()> {
+    // Every player is assigned a token, so there can't be more players than tokens
+    let max_players = AUTO_TOKENS.len();
+    let count = prompt("Number of players", Some(2), |input| {
+        match input.parse::<usize>() {
+            Ok(count) if (2..=max_players).contains(&count) => Ok(count),
+            _ => Err(format!("Please enter a number from 2 to {}.", max_players)),
+        }
+    })?;
+
+    let mut names: Vec<String> = Vec::new();
+    for index in 1..=count {
+        let default = format!("Player {}", index);
+        let name = prompt(
+            &format!("Name of player {}", index),
+            Some(default),
+            |input| {
+                if names.iter().any(|name| name == input) {
+                    Err(format!("{} is already playing.", input))
+                } else {
+                    Ok(input.to_string())
+                }
+            },
+        )?;
+        names.push(name);
+    }
+
+    let players: Vec<Player> = names.iter().map(Player::new).collect();
+    loop {
+        let rows = prompt("Number of rows", Some(args.rows), parse_number)?;
+        let cols = prompt("Number of columns", Some(args.cols), parse_number)?;
+        let tokens_to_win = prompt(
+            "Tokens in a row to win",
+            Some(args.tokens_to_win),
+            parse_number,
+        )?;
+
+        // Let the game decide whether the settings work together
+        match Game::new(rows, cols, tokens_to_win, players.clone()) {
+            Ok(_) => {
+                args.rows = rows;
+                args.cols = cols;
+                args.tokens_to_win = tokens_to_win;
+                break;
+            }
+            Err(err) => println!("{}", err),
+        }
+    }
+
+    args.players = names;
+    Ok(())
+}
+
+/// Asks `question` until the answer is accepted by `parse`.
+///
+/// An empty answer selects the default, if there is one.
+fn prompt<T: Display>(
+    question: &str,
+    default: Option<T>,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> io::Result<T> {
+    let mut default = default;
+
+    loop {
+        match &default {
+            Some(default) => print!("{} [{}]: ", question, default),
+            None => print!("{}: ", question),
+        }
+        io::stdout().flush()?;
+
+        let line = StdinInput::default().read_line()?;
+        if line.is_empty() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+
+        let input = line.trim();
+        if input.is_empty() {
+            if let Some(default) = default.take() {
+                return Ok(default);
+            }
+            continue;
+        }
+
+        match parse(input) {
+            Ok(value) => return Ok(value),
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+/// Parses a positive number.
+fn parse_number(input: &str) -> Result<usize, String> {
+    match input.parse::<usize>() {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err("Please enter a positive number.".to_string()),
+    }
+}
@@ -0,0 +1,132 @@
+//! Statistics module
+//!
+//! Contains the `GameStats` struct, which keeps track of the moves made during a game, how long
+//! each player thought about them, and how long the game took.
+//!
+//! Recording a move only bumps a counter, so keeping statistics costs nothing noticeable even
+//! when thousands of games are played in a row.
+
+use std::time::{Duration, Instant};
+
+/// Represents how long a player took to choose their moves.
+///
+/// For people this is the time from being prompted until their move was entered, for computer
+/// players the time spent computing it.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ThinkTime {
+    /// The number of moves timed.
+    pub moves: usize,
+    /// The time spent on all of them.
+    pub total: Duration,
+    /// The time spent on the slowest move.
+    pub max: Duration,
+}
+
+impl ThinkTime {
+    /// Returns the average time spent per move, or zero if no move was timed.
+    pub fn average(&self) -> Duration {
+        self.total
+            .checked_div(self.moves as u32)
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Represents statistics gathered while a game is played.
+#[derive(Clone, Debug, Default)]
+pub struct GameStats {
+    /// The number of moves made by each player, in player order.
+    moves_per_player: Vec<usize>,
+    /// How long each player took to choose their moves, in player order.
+    think_times: Vec<ThinkTime>,
+    /// When the first move was made.
+    started: Option<Instant>,
+    /// When the game ended.
+    ended: Option<Instant>,
+}
+
+impl GameStats {
+    /// Creates empty statistics for a game of `player_count` players.
+    pub fn new(player_count: usize) -> Self {
+        Self {
+            moves_per_player: vec![0; player_count],
+            think_times: vec![ThinkTime::default(); player_count],
+            started: None,
+            ended: None,
+        }
+    }
+
+    /// Records a move made by the player at `player_index`.
+    pub fn record_move(&mut self, player_index: usize) {
+        if player_index >= self.moves_per_player.len() {
+            self.moves_per_player.resize(player_index + 1, 0);
+        }
+
+        self.moves_per_player[player_index] += 1;
+        self.started.get_or_insert_with(Instant::now);
+    }
+
+    /// Records that the player at `player_index` took `time` to choose a move.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use connect_four::game::stats::GameStats;
+    /// # use std::time::Duration;
+    /// let mut stats = GameStats::new(2);
+    /// stats.record_think_time(0, Duration::from_millis(100));
+    /// stats.record_think_time(0, Duration::from_millis(300));
+    ///
+    /// let alice = stats.think_times()[0];
+    /// assert_eq!(alice.moves, 2);
+    /// assert_eq!(alice.average(), Duration::from_millis(200));
+    /// assert_eq!(alice.max, Duration::from_millis(300));
+    /// assert_eq!(stats.think_times()[1].average(), Duration::ZERO);
+    /// ```
+    pub fn record_think_time(&mut self, player_index: usize, time: Duration) {
+        if player_index >= self.think_times.len() {
+            self.think_times
+                .resize(player_index + 1, ThinkTime::default());
+        }
+
+        let think_time = &mut self.think_times[player_index];
+        think_time.moves += 1;
+        think_time.total += time;
+        think_time.max = think_time.max.max(time);
+    }
+
+    /// Takes back a move made by the player at `player_index`.
+    pub fn undo_move(&mut self, player_index: usize) {
+        if let Some(moves) = self.moves_per_player.get_mut(player_index) {
+            *moves = moves.saturating_sub(1);
+        }
+    }
+
+    /// Records that the game has ended. Later calls keep the first end time.
+    pub fn record_end(&mut self) {
+        self.ended.get_or_insert_with(Instant::now);
+    }
+
+    /// Returns the total number of moves made.
+    pub fn total_moves(&self) -> usize {
+        self.moves_per_player.iter().sum()
+    }
+
+    /// Returns the number of moves made by each player, in player order.
+    pub fn moves_per_player(&self) -> &[usize] {
+        &self.moves_per_player
+    }
+
+    /// Returns how long each player took to choose their moves, in player order.
+    pub fn think_times(&self) -> &[ThinkTime] {
+        &self.think_times
+    }
+
+    /// Returns the time from the first move until the game ended, or until now if it is still
+    /// going. Returns zero before the first move.
+    pub fn duration(&self) -> Duration {
+        match self.started {
+            Some(started) => self.ended.unwrap_or_else(Instant::now) - started,
+            None => Duration::ZERO,
+        }
+    }
+}
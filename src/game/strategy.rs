@@ -0,0 +1,533 @@
+//! Strategy module
+//!
+//! Contains the `Strategy` trait, which decides the moves of a player, and the `HumanStrategy`
+//! that asks a person at the terminal.
+//!
+//! Each player in a `Game` is driven by a strategy, so games can mix human and programmatic
+//! players freely. Computer opponents live in the `ai` module.
+
+use super::board::Board;
+use super::input::{InputSource, StdinInput};
+use super::output::{Output, StdoutOutput};
+use super::render::DisplaySettings;
+use super::util::Labels;
+use super::Player;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Represents an action a player can take on their turn.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    /// Drop a token into the column.
+    Drop(usize),
+    /// Take back the last move.
+    Undo,
+    /// Play the last undone move again.
+    Redo,
+    /// Remove one of the player's own tokens from the bottom of the column (Pop Out variant).
+    PopOut(usize),
+    /// Put a token in the empty cell at (row, column), regardless of gravity (free placement).
+    Place(usize, usize),
+    /// Abort the game.
+    Quit,
+    /// Show a suggested move without playing it.
+    Hint,
+    /// The player ran out of time to choose.
+    TimedOut,
+    /// Give up the game.
+    Forfeit,
+    /// Write the board to the file and keep playing.
+    Save(PathBuf),
+}
+
+/// Connects a strategy to the person whose turn it is.
+///
+/// A `Game` lends its own input source, output and display settings, so players type into the
+/// same source, are prompted where the game is shown and see the same labels for the whole game.
+pub struct Console<'a> {
+    /// Supplies the lines the player types.
+    pub input: &'a mut dyn InputSource,
+    /// Shows prompts and rejected input to the player.
+    pub output: &'a mut dyn Output,
+    /// How boards and lanes are shown to the player.
+    pub display: &'a DisplaySettings,
+}
+
+/// Chooses which column a player drops their token into.
+pub trait Strategy {
+    /// Returns the column to play for `me` on the given board.
+    ///
+    /// `opponents` are listed in the order they will move after `me`.
+    fn choose_column(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize;
+
+    /// Returns the action to take for `me` on the given board.
+    ///
+    /// Defaults to dropping a token into the column picked by `choose_column`.
+    fn choose_command(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        Command::Drop(self.choose_column(board, me, opponents, tokens_to_win))
+    }
+
+    /// Returns the action to take for `me`, talking to the player through `console`.
+    ///
+    /// Defaults to `choose_command`, for strategies that don't read typed input.
+    fn choose_command_from(
+        &self,
+        console: &mut Console,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        let _ = console;
+        self.choose_command(board, me, opponents, tokens_to_win)
+    }
+
+    /// Returns whether a person makes the choices of this strategy.
+    ///
+    /// A game without interactive players plays itself and is only watched.
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// A strategy that prompts a person for every move.
+///
+/// Input is read from the game's `Console`, or from stdin when used on its own.
+pub struct HumanStrategy {
+    /// How long the player has to choose, if they are on the clock.
+    timeout: Option<Duration>,
+}
+
+impl HumanStrategy {
+    /// Creates a strategy that waits as long as the player needs.
+    pub fn new() -> Self {
+        Self { timeout: None }
+    }
+
+    /// Creates a strategy that gives the player `timeout` to choose each turn.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+        }
+    }
+
+    /// Reads a line, or returns None if the deadline passes first.
+    ///
+    /// The time left is updated on the console's output every second.
+    fn read_line_before(console: &mut Console, deadline: Instant) -> Option<io::Result<String>> {
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            console.output.update_countdown(left);
+            if left.is_zero() {
+                return None;
+            }
+
+            let timeout = left.min(Duration::from_secs(1));
+            if let Some(result) = console.input.read_line_timeout(timeout) {
+                return Some(result);
+            }
+        }
+    }
+
+    /// Describes which columns can be played, e.g. "Column must be between 0 and 6 and not
+    /// full; columns still open: 0,2,5".
+    ///
+    /// With sideways gravity the lanes are rows, and are named so.
+    fn open_columns_hint(board: &Board, labels: &Labels) -> String {
+        let open: Vec<String> = board
+            .open_columns()
+            .into_iter()
+            .map(|col| labels.lane(col))
+            .collect();
+        let (lane, lanes) = if board.is_sideways() {
+            ("Row", "rows")
+        } else {
+            ("Column", "columns")
+        };
+
+        format!(
+            "{} must be between {} and {} and not full; {} still open: {}",
+            lane,
+            labels.lane(0),
+            labels.lane(board.lane_count() - 1),
+            lanes,
+            open.join(",")
+        )
+    }
+
+    /// Prompts the player on the console until they enter a valid column.
+    ///
+    /// Commands other than a drop can't be carried out when only a column is asked for, so the
+    /// player is asked again. Once the player quits or gives up, input is closed or time runs out,
+    /// the first open column is played instead.
+    fn choose_column_from(
+        &self,
+        console: &mut Console,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize {
+        loop {
+            match self.choose_command_from(console, board, me, opponents, tokens_to_win) {
+                Command::Drop(col) => return col,
+                Command::Quit | Command::TimedOut | Command::Forfeit => {
+                    return board.open_columns().first().copied().unwrap_or(0)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Default for HumanStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for HumanStrategy {
+    /// Prompts the player on stdin until they enter a valid column.
+    /// Returns the column number entered by the player.
+    fn choose_column(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize {
+        let mut console = Console {
+            input: &mut StdinInput::default(),
+            output: &mut StdoutOutput::default(),
+            display: &DisplaySettings::default(),
+        };
+        self.choose_column_from(&mut console, board, me, opponents, tokens_to_win)
+    }
+
+    /// Prompts the player on stdin for a valid column or command.
+    fn choose_command(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        let mut console = Console {
+            input: &mut StdinInput::default(),
+            output: &mut StdoutOutput::default(),
+            display: &DisplaySettings::default(),
+        };
+        self.choose_command_from(&mut console, board, me, opponents, tokens_to_win)
+    }
+
+    /// Prompts the player on the console for a valid column or command.
+    ///
+    /// With a timeout, the clock keeps running while invalid input is corrected.
+    fn choose_command_from(
+        &self,
+        console: &mut Console,
+        board: &Board,
+        me: &Player,
+        _opponents: &[Player],
+        _tokens_to_win: usize,
+    ) -> Command {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let labels = console.display.labels;
+        let lane = if board.is_sideways() { "row" } else { "column" };
+
+        loop {
+            if let Some(deadline) = deadline {
+                let left = deadline.saturating_duration_since(Instant::now());
+                console
+                    .output
+                    .show_message(&format!("Time left: {}s", left.as_secs_f64().ceil()));
+            }
+            if labels.alpha {
+                console.output.show_message("Please enter a column to play (or 'undo', 'redo', 'hint', '?<column>' to preview, 'ff' to forfeit, 's <file>' to save the board, 'quit'): ");
+            } else {
+                console.output.show_message(&format!("Please enter a {0} to play (or 'u' to undo, 'r' to redo, 'h' for a hint, '?<{0}>' to preview, 'ff' to forfeit, 's <file>' to save the board, 'q' to quit): ", lane));
+            }
+
+            let result = match deadline {
+                Some(deadline) => match Self::read_line_before(console, deadline) {
+                    Some(result) => result,
+                    None => return Command::TimedOut,
+                },
+                None => console.input.read_line(),
+            };
+
+            let input_line = match result {
+                // Input was closed, so no more moves will come
+                Ok(line) if line.is_empty() => return Command::Quit,
+                Ok(line) => line,
+                Err(_) => {
+                    console
+                        .output
+                        .show_error("Failed to read input. Please try again.");
+                    continue;
+                }
+            };
+
+            let input = input_line.trim();
+
+            // With alpha labels a letter naming a column is a move, not a command
+            if labels.alpha && input.len() == 1 {
+                if let Ok(Some(col)) = labels.parse_lane(input) {
+                    if col < board.lane_count() {
+                        if board.valid_move(col) {
+                            return Command::Drop(col);
+                        }
+                        console.output.show_error(&format!(
+                            "Invalid move. {}",
+                            Self::open_columns_hint(board, &labels)
+                        ));
+                        continue;
+                    }
+                }
+            }
+
+            if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
+                return Command::Quit;
+            }
+            if input.eq_ignore_ascii_case("u") || input.eq_ignore_ascii_case("undo") {
+                return Command::Undo;
+            }
+            if input.eq_ignore_ascii_case("r") || input.eq_ignore_ascii_case("redo") {
+                return Command::Redo;
+            }
+            if input.eq_ignore_ascii_case("h") || input.eq_ignore_ascii_case("hint") {
+                return Command::Hint;
+            }
+            if input.eq_ignore_ascii_case("ff") {
+                return Command::Forfeit;
+            }
+            if let Some(path) = input
+                .strip_prefix(['s', 'S'])
+                .filter(|path| path.starts_with(' '))
+            {
+                return Command::Save(PathBuf::from(path.trim()));
+            }
+            // Show where a token would land, so it can be dropped by entering the column after
+            if let Some(col) = input.strip_prefix('?') {
+                match labels.parse_lane(col.trim()) {
+                    Ok(col) => match col.and_then(|col| Some((col, board.preview(col, me)?))) {
+                        Some((col, preview)) => {
+                            let (row, _) = preview.ghost.unwrap_or_default();
+                            console.output.render_board(&preview, console.display);
+                            console.output.show_message(&format!(
+                                "Your token would land in row {}. Enter {} to drop it there.",
+                                labels.row(row),
+                                labels.lane(col)
+                            ));
+                        }
+                        None => console.output.show_error(&format!(
+                            "Invalid move. {}",
+                            Self::open_columns_hint(board, &labels)
+                        )),
+                    },
+                    Err(_) => console.output.show_error(&format!(
+                        "Invalid input. Please enter a {} after '?'.",
+                        lane
+                    )),
+                }
+                continue;
+            }
+            if let Some(col) = input.strip_prefix(['p', 'P']) {
+                match labels.parse_lane(col.trim()) {
+                    Ok(Some(col)) => return Command::PopOut(col),
+                    Ok(None) => {
+                        console
+                            .output
+                            .show_error("Invalid move. Column is out of range.");
+                        continue;
+                    }
+                    Err(_) => {
+                        console.output.show_error(&format!(
+                            "Invalid input. Please enter a {} after 'p'.",
+                            lane
+                        ));
+                        continue;
+                    }
+                }
+            }
+
+            if let Some((row, col)) = input.split_once(',') {
+                let row = row
+                    .trim()
+                    .parse::<usize>()
+                    .map(|label| labels.lane_from_label(label));
+                let col = labels.parse_lane(col.trim());
+                match (row, col) {
+                    (Ok(Some(row)), Ok(Some(col))) => return Command::Place(row, col),
+                    (Ok(_), Ok(_)) => console
+                        .output
+                        .show_error("Invalid move. Cell is out of range."),
+                    _ => console
+                        .output
+                        .show_error("Invalid input. Please enter a row and column, e.g. '2,3'."),
+                }
+                continue;
+            }
+
+            match labels.parse_lane(input) {
+                Ok(Some(value)) if board.valid_move(value) => return Command::Drop(value),
+                Ok(_) => console.output.show_error(&format!(
+                    "Invalid move. {}",
+                    Self::open_columns_hint(board, &labels)
+                )),
+                Err(_) if labels.alpha => console
+                    .output
+                    .show_error("Invalid input. Please enter a column letter."),
+                Err(_) => console
+                    .output
+                    .show_error("Invalid input. Please enter a valid integer."),
+            }
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::Gravity;
+    use crate::game::input::ScriptedInput;
+    use crate::game::util::ColumnLabels;
+    use crate::GameStatus;
+
+    /// Keeps the errors shown to the player.
+    #[derive(Default)]
+    struct Errors(Vec<String>);
+
+    impl Output for Errors {
+        fn clear(&mut self) {}
+        fn render_board(&mut self, _board: &Board, _display: &DisplaySettings) {}
+        fn show_message(&mut self, _message: &str) {}
+        fn show_error(&mut self, message: &str) {
+            self.0.push(message.to_string());
+        }
+        fn announce_turn(&mut self, _player: &Player) {}
+        fn announce_result(&mut self, _status: &GameStatus, _message: &str) {}
+    }
+
+    /// Enters `lines` at the prompt of a 2x3 board with a full middle column, and returns the
+    /// command chosen along with the errors shown.
+    fn enter(lines: &[&str], labels: Labels) -> (Command, Vec<String>) {
+        let alice = Player::with_token("Alice", 'a');
+        let mut board = Board::new(2, 3);
+        board.place_token(1, alice.clone()).unwrap();
+        board.place_token(1, alice.clone()).unwrap();
+
+        let mut errors = Errors::default();
+        let display = DisplaySettings {
+            labels,
+            ..DisplaySettings::default()
+        };
+        let mut console = Console {
+            input: &mut ScriptedInput::new(lines.iter().copied()),
+            output: &mut errors,
+            display: &display,
+        };
+        let command =
+            HumanStrategy::new().choose_command_from(&mut console, &board, &alice, &[], 2);
+
+        (command, errors.0)
+    }
+
+    #[test]
+    fn rejected_columns_list_the_open_columns() {
+        let (command, errors) = enter(&["1", "3", "?7", "0"], Labels::default());
+
+        assert_eq!(command, Command::Drop(0));
+        let expected =
+            "Invalid move. Column must be between 0 and 2 and not full; columns still open: 0,2";
+        assert_eq!(errors, vec![expected; 3]);
+    }
+
+    #[test]
+    fn one_indexed_columns_are_played_from_zero() {
+        let one_indexed = Labels::new(true, ColumnLabels::Numeric, 3);
+
+        assert_eq!(enter(&["1"], one_indexed), (Command::Drop(0), vec![]));
+        assert_eq!(enter(&["3"], one_indexed), (Command::Drop(2), vec![]));
+
+        let (command, errors) = enter(&["0", "4", "3"], one_indexed);
+        assert_eq!(command, Command::Drop(2));
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn rejected_columns_are_listed_with_the_labels_players_see() {
+        let one_indexed = Labels::new(true, ColumnLabels::Numeric, 3);
+        let (command, errors) = enter(&["0", "2", "1"], one_indexed);
+
+        assert_eq!(command, Command::Drop(0));
+        let expected =
+            "Invalid move. Column must be between 1 and 3 and not full; columns still open: 1,3";
+        assert_eq!(errors, vec![expected; 2]);
+
+        let alpha = Labels::new(false, ColumnLabels::Alpha, 3);
+        let (command, errors) = enter(&["b", "?b", "c"], alpha);
+
+        assert_eq!(command, Command::Drop(2));
+        let expected =
+            "Invalid move. Column must be between A and C and not full; columns still open: A,C";
+        assert_eq!(errors, vec![expected; 2]);
+    }
+
+    #[test]
+    fn choosing_a_column_stops_asking_once_input_is_closed() {
+        let alice = Player::with_token("Alice", 'a');
+        let mut board = Board::new(2, 3);
+        board.place_token(0, alice.clone()).unwrap();
+        board.place_token(0, alice.clone()).unwrap();
+
+        let mut console = Console {
+            input: &mut ScriptedInput::new(["u", "h"]),
+            output: &mut Errors::default(),
+            display: &DisplaySettings::default(),
+        };
+        let col = HumanStrategy::new().choose_column_from(&mut console, &board, &alice, &[], 2);
+
+        assert_eq!(col, 1);
+    }
+
+    #[test]
+    fn rejected_lanes_are_named_rows_with_sideways_gravity() {
+        let alice = Player::with_token("Alice", 'a');
+        let mut board = Board::new(2, 3);
+        board.gravity = Gravity::Left;
+        for _ in 0..3 {
+            board.place_token(0, alice.clone()).unwrap();
+        }
+
+        let mut errors = Errors::default();
+        let mut console = Console {
+            input: &mut ScriptedInput::new(["2", "0", "1"]),
+            output: &mut errors,
+            display: &DisplaySettings::default(),
+        };
+        let command =
+            HumanStrategy::new().choose_command_from(&mut console, &board, &alice, &[], 2);
+
+        assert_eq!(command, Command::Drop(1));
+        let expected = "Invalid move. Row must be between 0 and 1 and not full; rows still open: 1";
+        assert_eq!(errors.0, vec![expected; 2]);
+    }
+}
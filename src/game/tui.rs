@@ -0,0 +1,226 @@
+//! Terminal UI module
+//!
+//! Contains the `TuiStrategy`, which lets a person pick a column with the arrow keys instead of
+//! typing its number.
+//!
+//! Keys are read in raw mode using `crossterm`. When stdin is not a terminal (e.g. input is piped)
+//! the strategy falls back to the typed prompt of `HumanStrategy`. The board is redrawn whenever
+//! the terminal is resized, so it is never left half drawn.
+
+use super::board::Board;
+use super::input::StdinInput;
+use super::output::StdoutOutput;
+use super::render::DisplaySettings;
+use super::strategy::{Command, Console, HumanStrategy, Strategy};
+use super::Player;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::cell::Cell;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+
+/// A strategy that moves a cursor over the columns with the arrow keys.
+pub struct TuiStrategy {
+    /// The currently selected column, kept between turns.
+    cursor: Cell<Option<usize>>,
+    /// How long the player has to choose, if they are on the clock.
+    timeout: Option<Duration>,
+}
+
+impl TuiStrategy {
+    /// Creates a new arrow-key strategy with the cursor over the center column.
+    pub fn new() -> Self {
+        Self {
+            cursor: Cell::new(None),
+            timeout: None,
+        }
+    }
+
+    /// Creates a new arrow-key strategy that gives the player `timeout` to choose each turn.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cursor: Cell::new(None),
+            timeout: Some(timeout),
+        }
+    }
+
+    /// Returns the typed prompt used when keys can't be read.
+    fn fallback(&self) -> HumanStrategy {
+        match self.timeout {
+            Some(timeout) => HumanStrategy::with_timeout(timeout),
+            None => HumanStrategy::new(),
+        }
+    }
+
+    /// Waits for the next key press in raw mode, giving up after `timeout` if one is given.
+    ///
+    /// Returns None if no key was pressed in time, or if the terminal was resized, so the caller
+    /// redraws either way. Raw mode swallows Ctrl-C, so it is reported as Esc.
+    fn read_key(timeout: Option<Duration>) -> io::Result<Option<KeyCode>> {
+        terminal::enable_raw_mode()?;
+
+        let key = loop {
+            if let Some(timeout) = timeout {
+                match event::poll(timeout) {
+                    Ok(true) => (),
+                    Ok(false) => break Ok(None),
+                    Err(err) => break Err(err),
+                }
+            }
+
+            match event::read() {
+                Ok(Event::Key(key))
+                    if key.kind == KeyEventKind::Press
+                        && key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.code == KeyCode::Char('c') =>
+                {
+                    break Ok(Some(KeyCode::Esc))
+                }
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => break Ok(Some(key.code)),
+                // The old frame was reflowed to the new size, so it has to be drawn again
+                Ok(Event::Resize(_, _)) => break Ok(None),
+                Ok(_) => continue,
+                Err(err) => break Err(err),
+            }
+        };
+
+        terminal::disable_raw_mode()?;
+        key
+    }
+}
+
+impl Default for TuiStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for TuiStrategy {
+    /// Lets the player select a column until they drop a token.
+    fn choose_column(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> usize {
+        loop {
+            if let Command::Drop(col) = self.choose_command(board, me, opponents, tokens_to_win) {
+                return col;
+            }
+        }
+    }
+
+    /// Lets the player move the cursor with Left/Right and drop with Enter.
+    fn choose_command(
+        &self,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        let mut console = Console {
+            input: &mut StdinInput::default(),
+            output: &mut StdoutOutput::default(),
+            display: &DisplaySettings::default(),
+        };
+        self.choose_command_from(&mut console, board, me, opponents, tokens_to_win)
+    }
+
+    /// Lets the player move the cursor with Left/Right and drop with Enter, showing the board on
+    /// the console's output.
+    ///
+    /// Keys are read from the terminal directly; the console's input is only used by the typed
+    /// fallback.
+    fn choose_command_from(
+        &self,
+        console: &mut Console,
+        board: &Board,
+        me: &Player,
+        opponents: &[Player],
+        tokens_to_win: usize,
+    ) -> Command {
+        if !io::stdin().is_terminal() {
+            return self.fallback().choose_command_from(
+                console,
+                board,
+                me,
+                opponents,
+                tokens_to_win,
+            );
+        }
+
+        let display = console.display;
+        let output = &mut *console.output;
+        let cols = board.lane_count();
+        let mut cursor = self.cursor.get().unwrap_or(cols / 2).min(cols - 1);
+        let mut notice: Option<&str> = None;
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            output.clear();
+            if board.is_sideways() {
+                output.show_message(&format!("Selected row: {}", display.labels.lane(cursor)));
+            } else {
+                output.show_message(&board.column_marker(cursor, display));
+            }
+            output.render_board(board, display);
+            output.announce_turn(me);
+            output.show_message(
+                "Use Left/Right to select a column and Enter to drop ('u' to undo, 'r' to redo, 'h' for a hint, 'f' to forfeit, 'q' to quit)."
+            );
+
+            // Wake up every second to update the countdown
+            let left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if let Some(left) = left {
+                if left.is_zero() {
+                    return Command::TimedOut;
+                }
+                output.show_message(&format!("Time left: {}s", left.as_secs_f64().ceil()));
+            }
+
+            if let Some(notice) = notice {
+                output.show_error(notice);
+            }
+
+            let key = match Self::read_key(left.map(|left| left.min(Duration::from_secs(1)))) {
+                Ok(Some(key)) => key,
+                Ok(None) => continue,
+                Err(_) => {
+                    return self.fallback().choose_command_from(
+                        console,
+                        board,
+                        me,
+                        opponents,
+                        tokens_to_win,
+                    )
+                }
+            };
+            notice = None;
+
+            match key {
+                KeyCode::Left => cursor = cursor.saturating_sub(1),
+                KeyCode::Right => cursor = (cursor + 1).min(cols - 1),
+                KeyCode::Enter if board.valid_move(cursor) => {
+                    self.cursor.set(Some(cursor));
+                    return Command::Drop(cursor);
+                }
+                KeyCode::Enter if board.is_banned(cursor) => {
+                    notice = Some("Invalid move. The center may not be played on the first move.")
+                }
+                KeyCode::Enter => notice = Some("Invalid move. Column is full."),
+                KeyCode::Char('u') => return Command::Undo,
+                KeyCode::Char('r') => return Command::Redo,
+                KeyCode::Char('p') => return Command::PopOut(cursor),
+                KeyCode::Char('h') => return Command::Hint,
+                KeyCode::Char('f') => return Command::Forfeit,
+                KeyCode::Char('q') | KeyCode::Esc => return Command::Quit,
+                _ => (),
+            }
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
@@ -1,29 +1,245 @@
 //! Utility functions for the game
 
+use super::ai::Difficulty;
+use super::board::Gravity;
+#[cfg(feature = "serde")]
+use super::protocol::Protocol;
+use super::render::Style;
+use super::{Placement, TieBreak, TimeoutAction, WinDirections};
 use clap::Parser;
-use std::io::{self, Write};
+use colored::Color;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
+use std::num::ParseIntError;
+use std::path::PathBuf;
+
+/// The number of columns that can be labelled with a letter.
+const ALPHABET_LEN: usize = 26;
+
+/// Represents how columns are labelled when shown to or entered by players.
+#[derive(Clone, Copy, PartialEq, Debug, Default, clap::ValueEnum)]
+pub enum ColumnLabels {
+    /// Columns are numbered, e.g. `0`, `1`, `2`.
+    #[default]
+    Numeric,
+    /// Columns are lettered, e.g. `A`, `B`, `C`.
+    Alpha,
+}
 
 /// Clears the terminal screen
+///
+/// Does nothing when stdout is not a terminal, so redirected output stays free of escape codes.
 pub fn clear_terminal() {
+    let mut stdout = io::stdout();
+    let is_terminal = stdout.is_terminal();
+    clear_screen(&mut stdout, is_terminal).unwrap();
+}
+
+/// Writes the escape codes that clear the screen to `out`, if it `is_terminal`.
+///
+/// Output that is redirected to a file or pipe is left untouched, so logs and captured output
+/// only contain what was printed.
+///
+/// ## Errors
+///
+/// - If writing to `out` fails.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::util;
+/// let mut captured = Vec::new();
+/// util::clear_screen(&mut captured, false).unwrap();
+/// assert!(!captured.contains(&0x1B));
+///
+/// util::clear_screen(&mut captured, true).unwrap();
+/// assert!(captured.starts_with(b"\x1B[2J"));
+/// ```
+pub fn clear_screen(out: &mut impl Write, is_terminal: bool) -> io::Result<()> {
+    if !is_terminal {
+        return Ok(());
+    }
+
     // Check the OS and clear the screen accordingly
     if cfg!(target_os = "windows") {
         // For Windows, we can use `cls`
-        print!("{}[2J", 27 as char); // ANSI escape sequence to clear the screen
-        io::stdout().flush().unwrap();
+        write!(out, "{}[2J", 27 as char)?; // ANSI escape sequence to clear the screen
     } else {
         // For Unix-like OS (Linux, macOS, etc.), use the clear command
-        print!("\x1B[2J\x1B[H"); // ANSI escape sequence to clear the screen and move cursor to top
-        io::stdout().flush().unwrap();
+        write!(out, "\x1B[2J\x1B[H")?; // ANSI escape sequence to clear the screen and move cursor to top
+    }
+    out.flush()
+}
+
+/// Represents how lanes are labelled when shown to or entered by players.
+///
+/// Boards and moves always use 0-based indices; only the labels players see change. The default
+/// numbers lanes from 0.
+///
+/// ## Example
+///
+/// ```
+/// # use connect_four::game::util::{ColumnLabels, Labels};
+/// let labels = Labels::new(false, ColumnLabels::Alpha, 7);
+/// assert_eq!(labels.parse_lane("c"), Ok(Some(2)));
+/// assert_eq!(labels.lane(2), "C");
+///
+/// // Letters don't reach past Z, so wide boards stay numbered
+/// let labels = Labels::new(false, ColumnLabels::Alpha, 30);
+/// assert_eq!(labels.lane(2), "2");
+/// assert!(labels.parse_lane("c").is_err());
+///
+/// let labels = Labels::new(true, ColumnLabels::Numeric, 7);
+/// assert_eq!(labels.lane(0), "1");
+/// assert_eq!(labels.parse_lane("0"), Ok(None));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Labels {
+    /// Whether lanes are numbered from 1 instead of 0.
+    pub one_indexed: bool,
+    /// Whether columns are labelled with letters.
+    pub alpha: bool,
+}
+
+impl Labels {
+    /// Creates the labels for a board with `cols` columns.
+    ///
+    /// There are only enough letters for 26 columns, so wider boards keep their numbers.
+    pub fn new(one_indexed: bool, labels: ColumnLabels, cols: usize) -> Self {
+        Self {
+            one_indexed,
+            alpha: labels == ColumnLabels::Alpha && cols <= ALPHABET_LEN,
+        }
+    }
+
+    /// Returns the label players see for the 0-based `lane`.
+    ///
+    /// This is a letter with alpha column labels, otherwise a number.
+    pub fn lane(&self, lane: usize) -> String {
+        if self.alpha && lane < ALPHABET_LEN {
+            char::from(b'A' + lane as u8).to_string()
+        } else {
+            self.row(lane)
+        }
+    }
+
+    /// Returns the number players see for the 0-based `row`.
+    ///
+    /// Rows are always numbered, even with alpha column labels.
+    pub fn row(&self, row: usize) -> String {
+        (row + self.one_indexed as usize).to_string()
+    }
+
+    /// Returns the 0-based lane for a number entered by a player.
+    ///
+    /// Returns None for `0` when lanes are numbered from 1.
+    pub fn lane_from_label(&self, label: usize) -> Option<usize> {
+        label.checked_sub(self.one_indexed as usize)
+    }
+
+    /// Parses a lane entered by a player.
+    ///
+    /// With alpha column labels a single letter selects a column, in either case. Numbers are
+    /// accepted as well and are handled like `lane_from_label`.
+    ///
+    /// ## Errors
+    ///
+    /// - If the input is neither a column letter nor a number.
+    pub fn parse_lane(&self, input: &str) -> Result<Option<usize>, ParseIntError> {
+        let mut chars = input.chars();
+        if let (true, Some(letter), None) = (self.alpha, chars.next(), chars.next()) {
+            if letter.is_ascii_alphabetic() {
+                return Ok(Some((letter.to_ascii_uppercase() as u8 - b'A') as usize));
+            }
+        }
+
+        input
+            .parse::<usize>()
+            .map(|label| self.lane_from_label(label))
     }
 }
 
+/// Parses a list of moves, one per line, each a player token followed by a column.
+///
+/// Blank lines and lines starting with `#` are ignored.
+///
+/// ```text
+/// a 3
+/// b 4
+/// ```
+pub fn parse_moves(input: &str) -> Result<Vec<(String, usize)>, String> {
+    let mut moves = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let token = parts.next().map(str::to_string);
+        let col = parts.next().and_then(|col| col.parse::<usize>().ok());
+
+        match (token, col, parts.next()) {
+            (Some(token), Some(col), None) => moves.push((token, col)),
+            _ => return Err(format!("Invalid move on line {}: {}", index + 1, line)),
+        }
+    }
+
+    Ok(moves)
+}
+
+/// Parses a handicap token, given as a player name and a column separated by a colon.
+///
+/// The column counts from 0, e.g. `Bob:3`.
+pub fn parse_handicap(input: &str) -> Result<(String, usize), String> {
+    let (name, col) = input
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected <PLAYER>:<COLUMN>, got {:?}", input))?;
+    let col = col
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("invalid column {:?}", col))?;
+
+    Ok((name.to_string(), col))
+}
+
+/// Parses a color by name, e.g. `red` or `bright-blue`, or as a hex code like `#ff8800`.
+///
+/// Words may be separated by a space, a dash or an underscore.
+pub fn parse_color(input: &str) -> Result<Color, String> {
+    input
+        .replace(['-', '_'], " ")
+        .parse()
+        .map_err(|_| format!("unknown color {:?}", input))
+}
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Args {
     /// The names of the players participating in the game.
-    #[arg(short, long, value_parser, num_args = 2..=26, value_delimiter = ' ')]
+    #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
     pub players: Vec<String>,
 
+    /// A file listing the players, one `name, token, difficulty` per line, instead of `--players`.
+    #[arg(long, conflicts_with = "players")]
+    pub players_file: Option<PathBuf>,
+
+    /// The tokens of the players, in the same order as `--players`.
+    /// Tokens may be up to two columns wide, such as emoji, and are assigned automatically when omitted.
+    #[arg(long, num_args = 1.., value_delimiter = ' ')]
+    pub tokens: Vec<String>,
+
+    /// The team of each player, in the same order as `--players`.
+    /// Players on the same team win together.
+    #[arg(long, num_args = 1.., value_delimiter = ' ')]
+    pub teams: Vec<usize>,
+
+    /// The color of each player's token, in the same order as `--players`.
+    /// Colors must differ, and are assigned automatically when omitted.
+    #[arg(long, num_args = 1.., value_delimiter = ' ', value_parser = parse_color)]
+    pub colors: Vec<Color>,
+
     /// The number of rows on the game board.
     #[arg(short, long, default_value = "6")]
     pub rows: usize,
@@ -35,4 +251,210 @@ pub struct Args {
     /// The number of connected tokens required to win the game.
     #[arg(short, long, default_value = "4")]
     pub tokens_to_win: usize,
+
+    /// The names of the players controlled by the computer.
+    #[arg(short, long, num_args = 1.., value_delimiter = ' ')]
+    pub bots: Vec<String>,
+
+    /// How strong the computer players are, in the same order as `--bots`.
+    /// A single difficulty applies to every bot. [default: hard]
+    #[arg(long, value_enum, num_args = 1.., value_delimiter = ' ')]
+    pub difficulty: Vec<Difficulty>,
+
+    /// How many moves ahead hard computer players search.
+    /// Scaled to the board size when omitted.
+    #[arg(short, long)]
+    pub depth: Option<usize>,
+
+    /// How many milliseconds hard computer players may think about each move.
+    /// They search as deep as they can in that time instead of to a fixed depth.
+    #[arg(long, value_name = "MS", conflicts_with = "depth", value_parser = clap::value_parser!(u64).range(1..))]
+    pub ai_time_ms: Option<u64>,
+
+    /// The difficulty of individual computer players, overriding `difficulty`.
+    /// Only set from the config file.
+    #[arg(skip)]
+    pub bot_difficulties: HashMap<String, Difficulty>,
+
+    /// The config file to read settings from. Defaults to `connect_four.toml` if it exists.
+    #[cfg(feature = "config")]
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// How many milliseconds to pause between moves when every player is a computer.
+    #[arg(long, default_value = "500")]
+    pub move_delay: u64,
+
+    /// Warn when a player takes longer than this many milliseconds to choose a move.
+    #[arg(long, value_name = "MS")]
+    pub slow_move: Option<u64>,
+
+    /// Animate tokens falling into place. Has no effect when output is not a terminal.
+    #[arg(long)]
+    pub animate: bool,
+
+    /// How many milliseconds a token takes to fall past each cell with `--animate`.
+    #[arg(long, default_value = "40")]
+    pub drop_speed: u64,
+
+    /// Announce the winner in a box instead of a plain line.
+    #[arg(long)]
+    pub banner: bool,
+
+    /// Record the game as an asciinema v2 cast, appending to the file if it exists.
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// Never clear the terminal, and only print the board after it changed.
+    #[arg(long, conflicts_with_all = ["tui", "animate"])]
+    pub quiet: bool,
+
+    /// Warn above the board about every move that would win on the spot.
+    #[arg(long)]
+    pub show_threats: bool,
+
+    /// Comment on the game, e.g. when a player sets up a double threat.
+    #[arg(long)]
+    pub commentary: bool,
+
+    /// Play this many random games between bots to check the game logic, then exit.
+    #[arg(long, hide = true)]
+    pub self_play: Option<usize>,
+
+    /// How the board is drawn: `ascii` brackets or a `unicode` grid with discs.
+    #[arg(long, value_enum, default_value_t = Style::Ascii)]
+    pub style: Style,
+
+    /// The width inside every cell of the board, for roomier cells than the tokens need.
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub cell_width: Option<usize>,
+
+    /// Number columns from 1 instead of 0.
+    #[arg(long)]
+    pub one_indexed: bool,
+
+    /// How columns are labelled: `numeric` or `alpha` letters. Boards wider than 26 columns are
+    /// always numbered.
+    #[arg(long, value_enum, default_value_t = ColumnLabels::Numeric)]
+    pub column_labels: ColumnLabels,
+
+    /// Disable colored tokens. Colors are also disabled when `NO_COLOR` is set.
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// The direction tokens fall in. With left or right gravity players choose a row.
+    #[arg(short, long, value_enum, default_value_t = Gravity::Down)]
+    pub gravity: Gravity,
+
+    /// Allow players to pop their own tokens out of the bottom row instead of dropping one.
+    #[arg(long)]
+    pub pop_out: bool,
+
+    /// Extra tokens dropped for weaker players before the game begins, e.g. `Bob:3`.
+    /// Columns count from 0.
+    #[arg(long, num_args = 1.., value_delimiter = ' ', value_parser = parse_handicap)]
+    pub handicap: Vec<(String, usize)>,
+
+    /// Only lines of exactly `--tokens-to-win` tokens win. Longer lines don't count.
+    #[arg(long)]
+    pub exact_length: bool,
+
+    /// How a full board without a winner is decided: a `draw`, the `longest-line`, or the most
+    /// `threats`, i.e. lines one token short of winning.
+    #[arg(long, value_enum, default_value_t = TieBreak::Draw)]
+    pub tiebreak: TieBreak,
+
+    /// End the game in a draw once this many moves were played without a winner.
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub max_moves: Option<usize>,
+
+    /// The most tokens each column can be stacked up to. Cells above stay empty.
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub max_height: Option<usize>,
+
+    /// Forbid the center column, or both center columns of an even board, on the first move.
+    #[arg(long)]
+    pub ban_first_center: bool,
+
+    /// Join the left and right edges of the board, so rows and diagonals wrap around.
+    #[arg(long)]
+    pub wrap: bool,
+
+    /// The directions lines may win in, e.g. `h,v` to rule out diagonals.
+    /// `h` is horizontal, `v` vertical and `d` both diagonals.
+    #[arg(long, default_value = "h,v,d")]
+    pub win_directions: WinDirections,
+
+    /// Where players may put their tokens. With free placement any empty cell can be taken.
+    #[arg(long, value_enum, default_value_t = Placement::Gravity)]
+    pub placement: Placement,
+
+    /// The index of the player who moves first, counting from 0.
+    #[arg(long, conflicts_with = "random_start")]
+    pub first_player: Option<usize>,
+
+    /// Pick the player who moves first at random.
+    #[arg(long)]
+    pub random_start: bool,
+
+    /// The seed for random decisions, so that games can be reproduced.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// The number of rounds in the match.
+    #[arg(long, default_value = "1")]
+    pub rounds: usize,
+
+    /// Step through the moves listed in a file instead of playing.
+    /// Each line holds a player token and a column, e.g. `a 3`.
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+
+    /// Host the game over WebSockets on this address instead of playing locally.
+    /// One client connects for each player, in the order of `--players`.
+    #[cfg(feature = "net")]
+    #[arg(long)]
+    pub serve: Option<String>,
+
+    /// Join a game hosted with `--serve` at this URL, e.g. `ws://127.0.0.1:9001`.
+    #[cfg(feature = "net")]
+    #[arg(long, conflicts_with = "serve")]
+    pub connect: Option<String>,
+
+    /// Keep the win, loss and draw records of every player in this file.
+    /// Defaults to `connect_four/stats.json` in the config directory.
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    pub stats_file: Option<PathBuf>,
+
+    /// Print the recorded standings and exit.
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    pub show_stats: bool,
+
+    /// Check that the game configuration is valid and exit without playing.
+    #[arg(long, conflicts_with = "replay")]
+    pub check_config: bool,
+
+    /// How the game talks to its players: `human` prompts, or `jsonl` to be played by another
+    /// program through stdin and stdout.
+    #[cfg(feature = "serde")]
+    #[arg(long, value_enum, default_value_t = Protocol::Human, conflicts_with_all = ["tui", "turn_timeout", "animate"])]
+    pub protocol: Protocol,
+
+    /// Log every game event to stderr.
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Select columns with the arrow keys instead of typing their number.
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Give human players this many seconds to choose each turn.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub turn_timeout: Option<u64>,
+
+    /// What happens when a player runs out of time: skip their turn or play a random column.
+    #[arg(long, value_enum, default_value_t = TimeoutAction::Skip, requires = "turn_timeout")]
+    pub timeout_action: TimeoutAction,
 }
@@ -0,0 +1,23 @@
+//! # Connect Four
+//!
+//! Library crate backing the `connect_four` CLI. It exposes the game logic so it can be embedded in other programs.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use connect_four::{Game, Player};
+//!
+//! let players = vec![Player::new("Alice"), Player::new("Bob")];
+//!
+//! match Game::new(6, 7, 4, players) {
+//!     Ok(mut game) => println!("{:?}", game.start().result),
+//!     Err(err) => eprintln!("Invalid game configuration: {}", err),
+//! }
+//! ```
+
+pub mod game;
+#[cfg(feature = "net")]
+pub mod net;
+
+// Re-export key types for easier access
+pub use game::{Game, GameConfig, GameError, GameStatus, Player};
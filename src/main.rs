@@ -6,7 +6,7 @@
 //!
 //! The objective of the game is to be the first to form a line of four discs horizontally, vertically, or diagonally.
 //!
-//! This implementation provides a command-line interface (CLI) version of the game. It supports up to 26 players and allows for customizable board dimensions. The default board size is 6x7. Each player is assigned a unique token (character), starting with `a` for the first player, followed by `b`, `c`, and so on for subsequent players.
+//! This implementation provides a command-line interface (CLI) version of the game. It supports up to 62 players and allows for customizable board dimensions. The default board size is 6x7. Each player is assigned a unique token (character), starting with `a` for the first player, followed by `b`, `c`, and so on for subsequent players, then `A` to `Z` and `0` to `9`. Players who choose their own tokens are not limited to these.
 //!
 //! ## Usage
 //!
@@ -18,9 +18,21 @@
 //!
 //! ### Options:
 //!
-//! - `-p`, `--players <PLAYERS> <PLAYERS>...`
+//! - `-p`, `--players <PLAYERS>...`
 //!   Specify the players participating in the game.
 //!
+//! - `--players-file <PLAYERS_FILE>`
+//!   Read the players from a file instead, one per line as `name, token, difficulty`. The token and difficulty are optional, and a difficulty makes the player a bot, e.g. `Computer, , medium`. Blank lines and lines starting with `#` are skipped.
+//!
+//! - `--tokens <TOKENS>...`
+//!   Specify the token of each player, in the same order as `--players`. Tokens may be up to two columns wide, so emoji work too, e.g. `--tokens 🔴 🟡`. Tokens are assigned automatically when omitted.
+//!
+//! - `--teams <TEAMS>...`
+//!   Specify the team of each player, in the same order as `--players`, e.g. `--teams 1 2 1 2` for a 2v2 game. Lines may mix the tokens of teammates, and the whole team wins together.
+//!
+//! - `--colors <COLORS>...`
+//!   Specify the color of each player's token, in the same order as `--players`, e.g. `--colors blue bright-red`. Colors must differ. Assigned automatically when omitted.
+//!
 //! - `-r`, `--rows <ROWS>`
 //!   Set the number of rows on the board. [default: 6]
 //!
@@ -30,21 +42,162 @@
 //! - `-t`, `--tokens-to-win <TOKENS_TO_WIN>`
 //!   Specify the number of connected tokens required to win. [default: 4]
 //!
+//! - `-b`, `--bots <BOTS>...`
+//!   Specify which players are controlled by the computer.
+//!
+//! - `--difficulty <DIFFICULTY>...`
+//!   Set how strong each bot is, in the same order as `--bots`: `easy` bots play random columns, `medium` bots win or block when they can, `hard` bots search several moves ahead. A single difficulty applies to every bot. [default: hard]
+//!
+//! - `-d`, `--depth <DEPTH>`
+//!   Set how many moves ahead hard bots search. Scaled to the board size when omitted.
+//!
+//! - `--ai-time-ms <MS>`
+//!   Let hard bots think for this many milliseconds per move, searching one move deeper at a time until the time is up, instead of to a fixed `--depth`.
+//!
+//! - `-g`, `--gravity <GRAVITY>`
+//!   Set the direction tokens fall in: `down`, `up`, `left` or `right`. With `left` or `right` players choose a row instead of a column. [default: down]
+//!
+//! - `--pop-out`
+//!   Play the Pop Out variant: on your turn you may remove one of your tokens from the bottom row.
+//!
+//! - `--exact-length`
+//!   Only lines of exactly `--tokens-to-win` tokens win. Longer lines, called overlines, don't count.
+//!
+//! - `--tiebreak <TIEBREAK>`
+//!   Decide a full board without a winner: `draw`, `longest-line` for the side with the longest run of tokens, or `threats` for the side with the most lines one token short of winning. Equal scores are still a draw. [default: draw]
+//!
+//! - `--max-moves <MAX_MOVES>`
+//!   End the game in a draw once this many moves were played without a winner, however many cells are still empty.
+//!
+//! - `--max-height <MAX_HEIGHT>`
+//!   Set the most tokens each column can be stacked up to. A column counts as full at this height, and the cells above it stay empty.
+//!
+//! - `--ban-first-center`
+//!   Forbid the opening move in the center column, or in either center column of a board with an even number of columns, to take away some of the first player's advantage.
+//!
+//! - `--wrap`
+//!   Join the left and right edges of the board, so a line may continue from the rightmost column to the leftmost. Rows and diagonals wrap around; tokens still fall straight down.
+//!
+//! - `--win-directions <WIN_DIRECTIONS>`
+//!   Set the directions lines may win in, as a comma separated list of `h` (horizontal), `v` (vertical) and `d` (both diagonals), e.g. `h,v` to rule out diagonal wins. [default: h,v,d]
+//!
+//! - `--handicap <PLAYER:COLUMN>...`
+//!   Drop extra tokens for weaker players before the game begins, e.g. `--handicap Bob:3 Bob:4`. Columns count from 0.
+//!
+//! - `--placement <PLACEMENT>`
+//!   Set where tokens may go: `gravity` drops them into a column, `free` also lets players enter `<row>,<column>` to take any empty cell. [default: gravity]
+//!
+//! - `--first-player <FIRST_PLAYER>`
+//!   Set the index of the player who moves first, counting from 0. [default: 0]
+//!
+//! - `--random-start`
+//!   Pick the player who moves first at random.
+//!
+//! - `--seed <SEED>`
+//!   Set the seed for random decisions so that games can be reproduced. This covers the starting player and the moves of easy bots.
+//!
+//! - `--rounds <ROUNDS>`
+//!   Play a match of several rounds, rotating the starting player and keeping score. [default: 1]
+//!
+//! - `--replay <REPLAY>`
+//!   Step through the moves listed in a file, one `<token> <column>` pair per line, pausing for Enter between moves.
+//!
+//! - `--serve <ADDR>`
+//!   Host the game over WebSockets, e.g. on `127.0.0.1:9001`. One client connects for each player. Requires the `net` feature.
+//!
+//! - `--connect <URL>`
+//!   Join a game hosted with `--serve`, e.g. at `ws://127.0.0.1:9001`. Requires the `net` feature.
+//!
+//! - `--stats-file <STATS_FILE>`
+//!   Keep the win, loss and draw records of every player in this file. Records are updated after every game. Defaults to `connect_four/stats.json` in the config directory. Requires the `serde` feature.
+//!
+//! - `--show-stats`
+//!   Print the recorded standings and exit. Requires the `serde` feature.
+//!
+//! - `--check-config`
+//!   Validate the players, tokens and board without starting a game. Exits with status 0 if the configuration is valid, or prints the problem and exits with status 1.
+//!
+//! - `--protocol <PROTOCOL>`
+//!   Set how the game talks to its players: `human` prompts at the terminal, `jsonl` lets another program play through stdin and stdout. With `jsonl` moves are read as one JSON object per line, e.g. `{"col":3}`, and the board, turns, errors and the result are written as JSON lines. Requires the `serde` feature. [default: human]
+//!
+//! - `-v`, `--verbose`
+//!   Log every game event to stderr.
+//!
+//! - `--tui`
+//!   Select columns with the arrow keys and drop a token with Enter.
+//!
+//! - `--turn-timeout <SECS>`
+//!   Give human players this many seconds to choose each turn.
+//!
+//! - `--timeout-action <ACTION>`
+//!   What happens when a player runs out of time: `skip` their turn or play a `random` column. [default: skip]
+//!
+//! - `--move-delay <MOVE_DELAY>`
+//!   Set how many milliseconds to pause between moves when every player is a bot, so the game can be watched. [default: 500]
+//!
+//! - `--slow-move <MS>`
+//!   Warn below the board when a player took longer than this many milliseconds to choose a move. The average and longest think time of every player are shown after the game either way.
+//!
+//! - `--animate`
+//!   Animate tokens falling into place. Has no effect when output is not a terminal.
+//!
+//! - `--drop-speed <DROP_SPEED>`
+//!   Set how many milliseconds an animated token takes to fall past each cell. [default: 40]
+//!
+//! - `--banner`
+//!   Announce the winner in a box drawn around the result instead of a plain line.
+//!
+//! - `--record <RECORD>`
+//!   Record the game as an [asciinema](https://asciinema.org) v2 cast that can be replayed with `asciinema play`. The recording is appended if the file exists, so every round of a match ends up in one cast.
+//!
+//! - `--quiet`
+//!   Never clear the terminal, and only print the board again after a move changed it. Useful in terminals where clearing is disorienting and when logging to a file.
+//!
+//! - `--show-threats`
+//!   Warn above the board whenever a player can win with their next drop, e.g. `Warning: Bob (b) can win in column 3`.
+//!
+//! - `--commentary`
+//!   Comment on the game as it is played, e.g. `Alice has a double threat — Bob can only block one!` when a move creates two winning threats at once.
+//!
+//! - `--style <STYLE>`
+//!   Set how the board is drawn: `ascii` cells in brackets or a `unicode` grid with round discs. [default: ascii]
+//!
+//! - `--cell-width <CELL_WIDTH>`
+//!   Set the width inside every cell of the board, e.g. `3` for `[ a ]`, so narrow boards look less cramped. Cells never get narrower than their tokens and column numbers need.
+//!
+//! - `--one-indexed`
+//!   Number columns from 1 instead of 0, both on the board and when entering moves.
+//!
+//! - `--column-labels <COLUMN_LABELS>`
+//!   Label columns with `numeric` indices or `alpha` letters, e.g. `A`, `B`, `C`. Letters are entered in either case. Boards wider than 26 columns and boards with sideways gravity are always numbered. With letters, type commands in full, e.g. `undo` or `hint`. [default: numeric]
+//!
+//! - `--no-color`
+//!   Disable colored tokens. Colors are also disabled when the `NO_COLOR` environment variable is set.
+//!
+//! - `--config <CONFIG>`
+//!   Read settings from a TOML file. Defaults to `connect_four.toml` in the current directory if it exists. Requires the `config` feature, which is enabled by default.
+//!
 //! - `-h`, `--help`
 //!   Display usage information.
 //!
+//! ### Environment variables:
+//!
+//! `CONNECT_FOUR_ROWS`, `CONNECT_FOUR_COLS` and `CONNECT_FOUR_TOKENS_TO_WIN` set the matching
+//! options. Settings are taken from the command line first, then the environment, then the
+//! config file, then the defaults.
+//!
 //! ## Roadmap
 //!
 //! Planned features for future development include:
 //!
-//! - [ ] WebSocket support for online multiplayer functionality.
-//! - [ ] An AI opponent to play against.
-//! - [ ] Make the game more colorful with [colored](https://docs.rs/colored/latest/colored/)
+//! - [x] WebSocket support for online multiplayer functionality.
+//! - [x] An AI opponent to play against.
+//! - [x] Make the game more colorful with [colored](https://docs.rs/colored/latest/colored/)
 //! - [ ] Add tests to ensure the game logic is correct.
 //! - [ ] Add README.md file with instructions on how to play the game.
-//! - [ ] Config game settings via a configuration file (env vars?).
-//! - [ ] Config game settings via interactive CLI prompts.
-//! - [ ] Select column to drop token via arrow keys.
+//! - [x] Config game settings via a configuration file (env vars?).
+//! - [x] Config game settings via interactive CLI prompts.
+//! - [x] Select column to drop token via arrow keys.
 //!
 //! ## Examples
 //!
@@ -58,28 +211,418 @@
 //!    Player::new("Bob"),
 //! ]
 //!
-//! let mut game = Game::new(rows, cols, tokens_to_win, players);
+//! let mut game = Game::new(rows, cols, tokens_to_win, players).expect("valid configuration");
 //! game.start();
 //! ```
 
-use clap::Parser;
-
-pub mod game;
-use game::{Game, Player};
-
-use game::util::Args;
+use connect_four::game::board::Gravity;
+use connect_four::game::cast::CastOutput;
+use connect_four::game::config;
+use connect_four::game::match_play::Match;
+use connect_four::game::output::{Output, QuietOutput, StdoutOutput};
+#[cfg(feature = "serde")]
+use connect_four::game::protocol::{JsonlOutput, JsonlStrategy, Protocol};
+#[cfg(feature = "serde")]
+use connect_four::game::records::{self, Records};
+use connect_four::game::render::DisplaySettings;
+use connect_four::game::rng::Rng;
+use connect_four::game::self_play;
+use connect_four::game::setup;
+use connect_four::game::strategy::HumanStrategy;
+use connect_four::game::tui::TuiStrategy;
+use connect_four::game::util::{self, Args, ColumnLabels, Labels};
+#[cfg(feature = "serde")]
+use connect_four::{game::event::GameEvent, GameStatus};
+use connect_four::{Game, GameError, Player};
+use std::io::{self, IsTerminal};
+use std::time::Duration;
 
 /// This is the main entry point for the Connect Four CLI game.
 fn main() {
-    // Parse the command-line arguments.
-    let args = Args::parse();
+    // Parse the command-line arguments and merge in the config file.
+    let mut args = match config::parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("Failed to read config: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // The `colored` crate already honors the NO_COLOR environment variable.
+    if args.no_color {
+        colored::control::set_override(false);
+    }
+
+    if let Some(games) = args.self_play {
+        let seed = args.seed.unwrap_or_else(Rng::time_seed);
+        match self_play::self_play(games, seed) {
+            Ok(()) => println!("Played {} games from seed {} without errors", games, seed),
+            Err(failures) => {
+                eprintln!("{}", failures);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    #[cfg(feature = "serde")]
+    if args.show_stats {
+        let path = args
+            .stats_file
+            .clone()
+            .unwrap_or_else(records::default_path);
+        println!("{}", Records::load(&path).standings());
+        return;
+    }
+
+    #[cfg(feature = "net")]
+    if let Some(url) = &args.connect {
+        if let Err(err) = connect_four::net::client::connect(url, &display_settings(&args)) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Ask for the players and board when none were given, if there is someone to ask.
+    if args.players.is_empty() && !args.check_config && io::stdin().is_terminal() {
+        if let Err(err) = setup::prompt_setup(&mut args) {
+            eprintln!("Setup aborted: {}", err);
+            std::process::exit(1);
+        }
+    }
+
+    // Without a terminal to set up the game from, the players must be given up front. A checked
+    // configuration reports missing players like any other problem with the game.
+    if args.players.len() < 2 && !args.check_config {
+        eprintln!("Please provide at least two players with --players Alice Bob");
+        std::process::exit(1);
+    }
+
+    if !args.tokens.is_empty() && args.tokens.len() != args.players.len() {
+        eprintln!(
+            "Invalid game configuration: {} tokens given for {} players.",
+            args.tokens.len(),
+            args.players.len()
+        );
+        std::process::exit(1);
+    }
+
+    if !args.colors.is_empty() && args.colors.len() != args.players.len() {
+        eprintln!(
+            "Invalid game configuration: {} colors given for {} players.",
+            args.colors.len(),
+            args.players.len()
+        );
+        std::process::exit(1);
+    }
+
+    if !args.teams.is_empty() && args.teams.len() != args.players.len() {
+        eprintln!(
+            "Invalid game configuration: {} teams given for {} players.",
+            args.teams.len(),
+            args.players.len()
+        );
+        std::process::exit(1);
+    }
 
     // Create a new player for each name provided.
-    let players = args
-        .players
+    let mut players = if args.tokens.is_empty() {
+        args.players
+            .iter()
+            .map(Player::new)
+            .collect::<Vec<Player>>()
+    } else {
+        args.players
+            .iter()
+            .zip(&args.tokens)
+            .map(|(name, token)| Player::with_token(name, token.clone()))
+            .collect::<Vec<Player>>()
+    };
+
+    for (player, &team) in players.iter_mut().zip(&args.teams) {
+        player.team = Some(team);
+    }
+    for (player, &color) in players.iter_mut().zip(&args.colors) {
+        player.color = Some(color);
+    }
+
+    if let Some(path) = &args.replay {
+        replay(path, &args, players);
+        return;
+    }
+
+    if args.difficulty.len() > 1 && args.difficulty.len() != args.bots.len() {
+        eprintln!(
+            "Invalid game configuration: {} difficulties given for {} bots.",
+            args.difficulty.len(),
+            args.bots.len()
+        );
+        std::process::exit(1);
+    }
+
+    // Every bot must refer to one of the players.
+    if let Some(name) = args.bots.iter().find(|name| !args.players.contains(name)) {
+        eprintln!("Unknown bot player: {}", name);
+        std::process::exit(1);
+    }
+
+    // So must every handicap.
+    if let Some((name, _)) = args
+        .handicap
+        .iter()
+        .find(|(name, _)| !args.players.contains(name))
+    {
+        eprintln!("Unknown handicap player: {}", name);
+        std::process::exit(1);
+    }
+
+    if args.check_config {
+        match build_game(&args, players) {
+            Ok(_) => println!("Configuration is valid."),
+            Err(err) => {
+                eprintln!("Invalid game configuration: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    #[cfg(feature = "net")]
+    if let Some(addr) = &args.serve {
+        let game = match new_game(&args, players) {
+            Ok(game) => game,
+            Err(err) => {
+                eprintln!("Invalid game configuration: {}", err);
+                std::process::exit(1);
+            }
+        };
+        if let Err(err) = connect_four::net::server::serve(addr, game) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.rounds > 1 {
+        let mut series = Match::new(args.rounds, move || build_game(&args, players.clone()));
+        if let Err(err) = series.play() {
+            eprintln!("Invalid game configuration: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    match build_game(&args, players) {
+        Ok(mut game) => {
+            game.start();
+        }
+        Err(err) => {
+            eprintln!("Invalid game configuration: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Returns how boards and lanes are shown, as given on the command line.
+fn display_settings(args: &Args) -> DisplaySettings {
+    // Players pick rows on sideways boards, and rows are always numbered.
+    let labels = match args.gravity {
+        Gravity::Left | Gravity::Right => ColumnLabels::Numeric,
+        Gravity::Down | Gravity::Up => args.column_labels,
+    };
+
+    DisplaySettings {
+        style: args.style,
+        cell_width: args.cell_width,
+        labels: Labels::new(args.one_indexed, labels, args.cols),
+    }
+}
+
+/// Creates a game with the players and rules given on the command line, without any of the
+/// strategies, output or records of a game played here.
+///
+/// Replays and hosted games are set up this way, so they follow the same rules as a local game.
+fn new_game(args: &Args, players: Vec<Player>) -> Result<Game, GameError> {
+    let mut game = Game::new(args.rows, args.cols, args.tokens_to_win, players)?;
+    game.display = display_settings(args);
+
+    game.board.gravity = args.gravity;
+    game.pop_out = args.pop_out;
+    game.placement = args.placement;
+    game.exact_length = args.exact_length;
+    game.tiebreak = args.tiebreak;
+    game.board.wrap = args.wrap;
+    game.board.max_height = args.max_height;
+    game.win_directions = args.win_directions;
+
+    // Every random decision of the game derives from one seed
+    let seed = args.seed.unwrap_or_else(Rng::time_seed);
+    game.set_seed(seed);
+
+    if let Some(index) = args.first_player {
+        game.set_first_player(index)?;
+    } else if args.random_start {
+        game.randomize_first_player(seed)?;
+    }
+
+    let handicap: Vec<(usize, usize)> = args
+        .handicap
         .iter()
-        .map(|name| Player::new(name))
-        .collect::<Vec<Player>>();
+        .filter_map(|(name, col)| {
+            let index = args.players.iter().position(|player| player == name)?;
+            Some((index, *col))
+        })
+        .collect();
+    game.max_moves = args.max_moves;
+    game.set_ban_first_center(args.ban_first_center);
+    game.apply_handicap(&handicap)?;
+
+    Ok(game)
+}
+
+/// Creates a game with the players and options given on the command line.
+fn build_game(args: &Args, players: Vec<Player>) -> Result<Game, GameError> {
+    let mut game = new_game(args, players)?;
+
+    if args.verbose {
+        eprintln!("[seed] {}", game.seed().unwrap_or_default());
+    }
+
+    // The game has a single observer, which both logs events and keeps the records
+    let verbose = args.verbose;
+    #[cfg(feature = "serde")]
+    let (records_path, record_players) = (
+        args.stats_file
+            .clone()
+            .unwrap_or_else(records::default_path),
+        game.players.clone(),
+    );
+    game.set_observer(Box::new(move |event| {
+        if verbose {
+            eprintln!("[event] {:?}", event);
+        }
+
+        #[cfg(feature = "serde")]
+        {
+            let status = match event {
+                GameEvent::WinDetected { player } => GameStatus::Win(player.clone()),
+                GameEvent::Draw => GameStatus::Draw,
+                _ => return,
+            };
+
+            let mut records = Records::load(&records_path);
+            records.record(&record_players, &status);
+            if let Err(err) = records.save(&records_path) {
+                eprintln!(
+                    "Warning: failed to save records to {}: {}",
+                    records_path.display(),
+                    err
+                );
+            }
+        }
+    }));
+
+    game.timeout_action = args.timeout_action;
+    game.move_delay = Duration::from_millis(args.move_delay);
+    game.slow_move = args.slow_move.map(Duration::from_millis);
+    if args.animate {
+        game.drop_delay = Some(Duration::from_millis(args.drop_speed));
+    }
+    game.show_threats = args.show_threats;
+    game.commentary = args.commentary;
+    let mut output: Box<dyn Output> = if args.quiet {
+        let mut output = QuietOutput::default();
+        output.banner = args.banner;
+        Box::new(output)
+    } else {
+        Box::new(StdoutOutput {
+            banner: args.banner,
+        })
+    };
+
+    // Let human players pick columns with the arrow keys, and put them on the clock.
+    let timeout = args.turn_timeout.map(Duration::from_secs);
+    for index in 0..args.players.len() {
+        match (args.tui, timeout) {
+            (true, Some(timeout)) => {
+                game.set_strategy(index, Box::new(TuiStrategy::with_timeout(timeout)))
+            }
+            (true, None) => game.set_strategy(index, Box::new(TuiStrategy::new())),
+            (false, Some(timeout)) => {
+                game.set_strategy(index, Box::new(HumanStrategy::with_timeout(timeout)))
+            }
+            (false, None) => (),
+        }
+    }
+
+    // Let another program play the human players through stdin and stdout.
+    #[cfg(feature = "serde")]
+    if args.protocol == Protocol::Jsonl {
+        output = Box::new(JsonlOutput);
+        for index in 0..args.players.len() {
+            game.set_strategy(index, Box::new(JsonlStrategy));
+        }
+    }
+
+    // Record everything shown, but don't touch the file when only checking the configuration.
+    if let Some(path) = args.record.as_ref().filter(|_| !args.check_config) {
+        output = match CastOutput::append(path, output) {
+            Ok(cast) => Box::new(cast),
+            Err(err) => {
+                eprintln!("Failed to record to {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        };
+    }
+    game.set_output(output);
+
+    // Hand the requested players over to the computer.
+    for (position, name) in args.bots.iter().enumerate() {
+        if let Some(index) = args.players.iter().position(|player| player == name) {
+            let difficulty = args
+                .bot_difficulties
+                .get(name)
+                .copied()
+                .or_else(|| match args.difficulty.as_slice() {
+                    [difficulty] => Some(*difficulty),
+                    difficulties => difficulties.get(position).copied(),
+                })
+                .unwrap_or_default();
+            let seed = game.player_seed(index);
+            let time_budget = args.ai_time_ms.map(Duration::from_millis);
+            let bot = difficulty.strategy(&game.board, seed, args.depth, time_budget);
+            game.set_strategy(index, bot);
+        }
+    }
+
+    Ok(game)
+}
+
+/// Steps through the moves stored in the file at `path`, on a game set up like `new_game`.
+fn replay(path: &std::path::Path, args: &Args, players: Vec<Player>) {
+    let moves = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|input| util::parse_moves(&input));
+    let moves = match moves {
+        Ok(moves) => moves,
+        Err(err) => {
+            eprintln!("Failed to read replay: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // Reject illegal replays before showing any of it
+    let checked = new_game(args, players.clone())
+        .and_then(|mut game| game.replay_moves(&moves).map(|_| game));
+    if let Err(err) = checked {
+        eprintln!("Invalid replay: {}", err);
+        std::process::exit(1);
+    }
 
-    Game::new(args.rows, args.cols, args.tokens_to_win, players).start();
+    let mut game = new_game(args, players).expect("configuration was validated by the replay");
+    if let Err(err) = game.watch_replay(&moves) {
+        eprintln!("Invalid replay: {}", err);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,205 @@
+//! Client module
+//!
+//! Joins a game hosted by the `server` module. The board is rendered with `Board::display_with`
+//! and moves are read with the same prompt as a local game.
+
+use super::{ClientMessage, NetError, ServerMessage};
+use crate::game::ai;
+use crate::game::board::Board;
+use crate::game::input::StdinInput;
+use crate::game::output::StdoutOutput;
+use crate::game::render::DisplaySettings;
+use crate::game::strategy::{Command, Console, HumanStrategy, Strategy};
+use crate::game::util;
+use crate::{GameStatus, Player};
+use std::net::TcpStream;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// What the server told the client when it joined.
+struct Seat {
+    index: usize,
+    players: Vec<Player>,
+    tokens_to_win: usize,
+}
+
+/// Joins the game hosted at `url`, e.g. `ws://127.0.0.1:9001`, and plays it until it ends.
+///
+/// The board and lanes are shown as set out by `display`.
+///
+/// ## Errors
+///
+/// - If the server can't be reached.
+/// - If the connection drops before the game is over.
+/// - If the server sends a message that doesn't follow the protocol.
+pub fn connect(url: &str, display: &DisplaySettings) -> Result<(), NetError> {
+    let (mut socket, _) = tungstenite::connect(url)?;
+    println!("Connected to {}, waiting for the other players...", url);
+
+    let mut seat: Option<Seat> = None;
+    let mut board: Option<Board> = None;
+    let mut notice: Option<String> = None;
+    let mut input = StdinInput::default();
+    let mut output = StdoutOutput::default();
+
+    loop {
+        let message = match socket.read() {
+            Ok(Message::Text(text)) => serde_json::from_str::<ServerMessage>(&text)
+                .map_err(|err| NetError::Protocol(format!("invalid message: {}", err)))?,
+            Ok(Message::Close(_)) | Err(tungstenite::Error::ConnectionClosed) => {
+                return Err(NetError::Protocol(
+                    "the server closed the connection".to_string(),
+                ))
+            }
+            // Pings are answered by tungstenite itself
+            Ok(_) => continue,
+            Err(err) => return Err(err.into()),
+        };
+
+        match message {
+            ServerMessage::Welcome {
+                index,
+                player,
+                players,
+                tokens_to_win,
+            } => {
+                println!("Joined as {}", player);
+                seat = Some(Seat {
+                    index,
+                    players,
+                    tokens_to_win,
+                });
+            }
+            ServerMessage::State {
+                board: state,
+                current_turn,
+                status,
+            } => {
+                let seat = seat.as_ref().ok_or_else(not_welcomed)?;
+                let player = seat.players.get(current_turn).ok_or_else(|| {
+                    NetError::Protocol(format!("no player at index {}", current_turn))
+                })?;
+
+                render(&state, display, &mut notice);
+                if matches!(status, GameStatus::Ongoing) && current_turn != seat.index {
+                    println!("Waiting for {}...", player);
+                }
+                board = Some(state);
+            }
+            ServerMessage::YourTurn => {
+                let seat = seat.as_ref().ok_or_else(not_welcomed)?;
+                let board = board.as_ref().ok_or_else(|| {
+                    NetError::Protocol("asked for a move before receiving the board".to_string())
+                })?;
+
+                let mut console = Console {
+                    input: &mut input,
+                    output: &mut output,
+                    display,
+                };
+                match choose_column(board, seat, &mut console, &mut notice) {
+                    Some(col) => send(&mut socket, &ClientMessage { col })?,
+                    None => {
+                        println!("Game aborted");
+                        // The server eliminates players whose connection closes
+                        let _ = socket.close(None);
+                        return Ok(());
+                    }
+                }
+            }
+            ServerMessage::Error { message } => notice = Some(message),
+            ServerMessage::PlayerLeft { index } => {
+                let seat = seat.as_ref().ok_or_else(not_welcomed)?;
+                if let Some(player) = seat.players.get(index) {
+                    println!("{} left the game", player);
+                }
+            }
+            ServerMessage::GameOver { status } => {
+                match status {
+                    GameStatus::Ongoing => println!("Game aborted"),
+                    GameStatus::Draw => println!("Draw!"),
+                    GameStatus::Win(player) => {
+                        println!("The winner is: {}", player)
+                    }
+                }
+                let _ = socket.close(None);
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Prompts the local player until they pick a column.
+///
+/// Returns None if they quit.
+fn choose_column(
+    board: &Board,
+    seat: &Seat,
+    console: &mut Console,
+    notice: &mut Option<String>,
+) -> Option<usize> {
+    let me = &seat.players[seat.index];
+    let opponents: Vec<Player> = (1..seat.players.len())
+        .map(|offset| seat.players[(seat.index + offset) % seat.players.len()].clone())
+        .collect();
+
+    loop {
+        println!("{}'s ({}) Turn", me.name, me.token);
+
+        let strategy = HumanStrategy::new();
+        match strategy.choose_command_from(console, board, me, &opponents, seat.tokens_to_win) {
+            Command::Drop(col) => return Some(col),
+            // Leaving the game is how a player forfeits online
+            Command::Quit | Command::Forfeit => return None,
+            Command::Hint => {
+                *notice = Some(
+                    match ai::suggest_column(board, me, &opponents, seat.tokens_to_win) {
+                        Some(col) => {
+                            format!("Hint: try column {}", console.display.labels.lane(col))
+                        }
+                        None => "No moves left.".to_string(),
+                    },
+                );
+            }
+            Command::Save(path) => {
+                *notice = Some(match board.save(&path) {
+                    Ok(()) => format!("Board saved to {}", path.display()),
+                    Err(err) => format!("Could not save the board to {}: {}", path.display(), err),
+                });
+            }
+            // Online players are never on the clock
+            Command::Undo
+            | Command::Redo
+            | Command::PopOut(_)
+            | Command::Place(..)
+            | Command::TimedOut => {
+                *notice = Some("That command is not available in online games.".to_string());
+            }
+        }
+
+        render(board, console.display, notice);
+    }
+}
+
+/// Clears the screen and shows the board, followed by the pending notice.
+fn render(board: &Board, display: &DisplaySettings, notice: &mut Option<String>) {
+    util::clear_terminal();
+
+    println!("{}", board.display_with(display));
+
+    if let Some(notice) = notice.take() {
+        println!("{}", notice);
+    }
+}
+
+fn send(socket: &mut Socket, message: &ClientMessage) -> Result<(), NetError> {
+    let json = serde_json::to_string(message).expect("messages are always serializable");
+    socket.send(Message::text(json))?;
+    Ok(())
+}
+
+fn not_welcomed() -> NetError {
+    NetError::Protocol("received a message before joining the game".to_string())
+}
@@ -0,0 +1,109 @@
+//! Networking module
+//!
+//! Contains a WebSocket server and client, and the JSON protocol spoken between them.
+//!
+//! Every message is a JSON object sent as a WebSocket text message. The server tags its messages
+//! with a `type` field; clients only ever send moves, e.g. `{"col":3}`.
+//!
+//! This module is only available with the `net` feature.
+
+pub mod client;
+pub mod server;
+
+use crate::game::board::Board;
+use crate::{GameError, GameStatus, Player};
+use std::{fmt, io};
+
+/// Represents a message sent from the server to a client.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The client joined the game as the player at `index`.
+    Welcome {
+        /// The index of the client's player.
+        index: usize,
+        /// The client's player.
+        player: Player,
+        /// All players, in turn order.
+        players: Vec<Player>,
+        /// The number of connected tokens required to win.
+        tokens_to_win: usize,
+    },
+    /// The state of the game after a move.
+    State {
+        /// The game board.
+        board: Board,
+        /// The index of the player whose turn it is.
+        current_turn: usize,
+        /// The status of the game.
+        status: GameStatus,
+    },
+    /// It is the client's turn to send a move.
+    YourTurn,
+    /// The client's last message was rejected.
+    Error {
+        /// Why the message was rejected.
+        message: String,
+    },
+    /// The player at `index` disconnected.
+    PlayerLeft {
+        /// The index of the player who left.
+        index: usize,
+    },
+    /// The game ended.
+    GameOver {
+        /// The final status. `Ongoing` means the game was aborted.
+        status: GameStatus,
+    },
+}
+
+/// Represents a message sent from a client to the server.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ClientMessage {
+    /// The column to drop the client's token into.
+    pub col: usize,
+}
+
+/// Represents an error while hosting or joining a networked game.
+#[derive(Debug)]
+pub enum NetError {
+    /// A socket operation failed.
+    Io(io::Error),
+    /// The WebSocket connection failed.
+    WebSocket(tungstenite::Error),
+    /// The game could not be set up.
+    Game(GameError),
+    /// The other side sent a message that doesn't follow the protocol.
+    Protocol(String),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(err) => write!(f, "Network error: {}", err),
+            NetError::WebSocket(err) => write!(f, "WebSocket error: {}", err),
+            NetError::Game(err) => write!(f, "Invalid game configuration: {}", err),
+            NetError::Protocol(message) => write!(f, "Protocol error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+impl From<io::Error> for NetError {
+    fn from(err: io::Error) -> Self {
+        NetError::Io(err)
+    }
+}
+
+impl From<tungstenite::Error> for NetError {
+    fn from(err: tungstenite::Error) -> Self {
+        NetError::WebSocket(err)
+    }
+}
+
+impl From<GameError> for NetError {
+    fn from(err: GameError) -> Self {
+        NetError::Game(err)
+    }
+}
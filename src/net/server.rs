@@ -0,0 +1,138 @@
+//! Server module
+//!
+//! Hosts a game over WebSockets. One client connects for each player, in player order, and is
+//! told which player it controls. After every move the server broadcasts the board, and it asks
+//! the client whose turn it is for a move.
+//!
+//! When a client disconnects mid-game its player is eliminated: the others play on, and the last
+//! player standing wins.
+
+use super::{ClientMessage, NetError, ServerMessage};
+use crate::Game;
+use std::net::{TcpListener, TcpStream};
+use tungstenite::{Message, WebSocket};
+
+/// A connected client, or `None` once it disconnected.
+type Client = Option<WebSocket<TcpStream>>;
+
+/// Hosts `game` on `addr`, waiting for a client to connect for each player.
+///
+/// Moves are applied with `Game::play_move`, so the rules the game was set up with, e.g. its
+/// gravity or win directions, are the same as for a local game.
+///
+/// ## Errors
+///
+/// - If the address can't be bound or a connection can't be accepted.
+pub fn serve(addr: &str, mut game: Game) -> Result<(), NetError> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Listening on {}", addr);
+
+    let mut clients: Vec<Client> = Vec::new();
+    for (index, player) in game.players.iter().enumerate() {
+        let (stream, peer) = listener.accept()?;
+        let mut socket = tungstenite::accept(stream).map_err(|err| match err {
+            tungstenite::HandshakeError::Failure(err) => NetError::WebSocket(err),
+            tungstenite::HandshakeError::Interrupted(_) => {
+                NetError::WebSocket(tungstenite::Error::ConnectionClosed)
+            }
+        })?;
+
+        println!("{} joined as {}", peer, player);
+        let welcome = ServerMessage::Welcome {
+            index,
+            player: player.clone(),
+            players: game.players.clone(),
+            tokens_to_win: game.tokens_to_win,
+        };
+        // A client that leaves before the game starts is treated like one leaving mid-game
+        clients.push(send(&mut socket, &welcome).ok().map(|_| socket));
+    }
+
+    loop {
+        broadcast(
+            &mut clients,
+            &ServerMessage::State {
+                board: game.board.clone(),
+                current_turn: game.current_turn(),
+                status: game.status(),
+            },
+        );
+
+        if game.status().is_over() {
+            broadcast(
+                &mut clients,
+                &ServerMessage::GameOver {
+                    status: game.status(),
+                },
+            );
+            return Ok(());
+        }
+
+        let turn = game.current_turn();
+        match read_move(&mut clients[turn]) {
+            Some(col) => {
+                if let Err(err) = game.play_move(col) {
+                    let error = ServerMessage::Error {
+                        message: err.to_string(),
+                    };
+                    send_to(&mut clients[turn], &error);
+                }
+            }
+            // Clients that dropped out earlier are noticed once it is their turn
+            None => {
+                println!("{} left the game", game.current_player().name);
+                clients[turn] = None;
+                broadcast(&mut clients, &ServerMessage::PlayerLeft { index: turn });
+                game.eliminate(turn)?;
+            }
+        }
+    }
+}
+
+/// Asks the client for a move until it sends a valid message.
+///
+/// Returns None if the client disconnected.
+fn read_move(client: &mut Client) -> Option<usize> {
+    let socket = client.as_mut()?;
+    send(socket, &ServerMessage::YourTurn).ok()?;
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(message) => return Some(message.col),
+                Err(err) => {
+                    let error = ServerMessage::Error {
+                        message: format!("Invalid message: {}", err),
+                    };
+                    send(socket, &error).ok()?;
+                }
+            },
+            Ok(Message::Close(_)) | Err(_) => return None,
+            // Pings are answered by tungstenite itself
+            Ok(_) => (),
+        }
+    }
+}
+
+/// Sends a message to a single socket.
+fn send(socket: &mut WebSocket<TcpStream>, message: &ServerMessage) -> Result<(), NetError> {
+    let json = serde_json::to_string(message).expect("messages are always serializable");
+    socket.send(Message::text(json))?;
+    Ok(())
+}
+
+/// Sends a message to a client, dropping the client if it can't be reached.
+fn send_to(client: &mut Client, message: &ServerMessage) {
+    if let Some(socket) = client.as_mut() {
+        if send(socket, message).is_err() {
+            *client = None;
+        }
+    }
+}
+
+/// Sends a message to every connected client.
+fn broadcast(clients: &mut [Client], message: &ServerMessage) {
+    for client in clients {
+        send_to(client, message);
+    }
+}